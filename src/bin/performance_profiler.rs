@@ -1,14 +1,33 @@
 // 3DGS 性能分析工具
 // 类似摄像头项目的 performance_profiler，实时监控各环节性能
 
+use bevy::diagnostic::{Diagnostic, DiagnosticPath, DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy::prelude::*;
-use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 use bevy_gaussian_splatting::{GaussianCamera, GaussianSplattingPlugin, PlanarGaussian3dHandle, CloudSettings};
 
+// 每帧排序（radix/depth sort）与光栅化（splat rasterize）的 GPU 耗时诊断路径。
+//
+// 这两个 `Diagnostic` 理应由 `GaussianSplattingPlugin` 的渲染图发布：在支持
+// `wgpu::Features::TIMESTAMP_QUERY` 的适配器上，给排序/光栅化两个 pass 插入
+// timestamp write、每帧 resolve，并作为 `gaussian/sort_ms`/`gaussian/raster_ms`
+// 两个 `Diagnostic` 发布出来。
+//
+// 这一半（生产者）不在本文件里，也不可能在本 crate 里实现——它需要改
+// `GaussianSplattingPlugin` 的渲染图，而那是上游、未 vendor 进本仓库的
+// `bevy_gaussian_splatting` crate 的代码。下面只是消费方脚手架：读取这两个
+// `Diagnostic`，如果上游某天发布了它们就能直接显示；在那之前它们永远是
+// `None`，界面永远显示 "N/A"。这不是该请求的完整实现，只是为将来的上游改动
+// 预留的消费端——分阶段 GPU 耗时 instrumentation 这个请求本身并未完成。
+const SORT_MS: DiagnosticPath = DiagnosticPath::const_new("gaussian/sort_ms");
+const RASTER_MS: DiagnosticPath = DiagnosticPath::const_new("gaussian/raster_ms");
+
 #[derive(Resource)]
 struct PerformanceStats {
     frame_times: Vec<f32>,
     max_samples: usize,
+    /// 最近一次读到的逐阶段 GPU 耗时（毫秒），上游诊断不可用时为 `None`。
+    last_sort_ms: Option<f32>,
+    last_raster_ms: Option<f32>,
 }
 
 impl Default for PerformanceStats {
@@ -16,6 +35,8 @@ impl Default for PerformanceStats {
         Self {
             frame_times: Vec::new(),
             max_samples: 300, // 5秒 @ 60fps
+            last_sort_ms: None,
+            last_raster_ms: None,
         }
     }
 }
@@ -23,6 +44,11 @@ impl Default for PerformanceStats {
 fn main() {
     println!("=== 3DGS 性能分析工具 ===\n");
     println!("实时监控渲染性能，识别瓶颈\n");
+    println!(
+        "⚠️  GPU 分阶段耗时（排序/光栅化）尚未实现：生产者需要改 GaussianSplattingPlugin\n\
+         的渲染图（上游 crate，未 vendor 进本仓库），本工具只有消费端脚手架。下面的\n\
+         \"排序\"/\"光栅化\" 两行会一直显示 N/A，直到那个上游改动落地。\n"
+    );
 
     App::new()
         .add_plugins(DefaultPlugins.set(WindowPlugin {
@@ -104,6 +130,15 @@ fn monitor_performance(
             }
         }
     }
+
+    stats.last_sort_ms = diagnostics
+        .get(&SORT_MS)
+        .and_then(Diagnostic::smoothed)
+        .map(|v| v as f32);
+    stats.last_raster_ms = diagnostics
+        .get(&RASTER_MS)
+        .and_then(Diagnostic::smoothed)
+        .map(|v| v as f32);
 }
 
 fn display_stats(
@@ -138,6 +173,16 @@ fn display_stats(
         }
     }
 
+    // 逐阶段 GPU 耗时（排序 vs 光栅化），需要上游插件支持 TIMESTAMP_QUERY 才有数据。
+    let sort_info = stats
+        .last_sort_ms
+        .map(|v| format!("{:.2}ms", v))
+        .unwrap_or_else(|| "N/A (需 TIMESTAMP_QUERY 支持)".to_string());
+    let raster_info = stats
+        .last_raster_ms
+        .map(|v| format!("{:.2}ms", v))
+        .unwrap_or_else(|| "N/A (需 TIMESTAMP_QUERY 支持)".to_string());
+
     // 性能评估
     let performance_rating = if avg_fps >= 55.0 {
         "✓ 优秀"
@@ -173,6 +218,10 @@ fn display_stats(
             \n\
             GPU 帧时间: {}\n\
             \n\
+            GPU 分阶段耗时:\n\
+              排序 (sort):     {}\n\
+              光栅化 (raster): {}\n\
+            \n\
             性能评级: {}\n\
             瓶颈分析: {}\n\
             \n\
@@ -191,11 +240,13 @@ fn display_stats(
             min_frame_time,
             max_frame_time,
             gpu_info,
+            sort_info,
+            raster_info,
             performance_rating,
             bottleneck,
             stats.frame_times.len(),
             avg_frame_time,
-            get_optimization_suggestions(avg_frame_time, max_frame_time)
+            get_optimization_suggestions(avg_frame_time, max_frame_time, stats.last_sort_ms, stats.last_raster_ms)
         );
     }
 
@@ -209,9 +260,26 @@ fn display_stats(
     }
 }
 
-fn get_optimization_suggestions(avg_frame_time: f32, max_frame_time: f32) -> String {
+/// 优化建议：当逐阶段 GPU 耗时可用时（上游发布了 `gaussian/sort_ms` /
+/// `gaussian/raster_ms` 诊断），优先按真正主导帧耗时的阶段给建议，而不是瞎猜。
+fn get_optimization_suggestions(
+    avg_frame_time: f32,
+    max_frame_time: f32,
+    sort_ms: Option<f32>,
+    raster_ms: Option<f32>,
+) -> String {
     let mut suggestions = Vec::new();
 
+    match (sort_ms, raster_ms) {
+        (Some(sort), Some(raster)) if raster > sort * 1.5 => {
+            suggestions.push("• 光栅化阶段是瓶颈：考虑视锥体剔除或降低点云密度");
+        }
+        (Some(sort), Some(raster)) if sort > raster * 1.5 => {
+            suggestions.push("• 排序阶段是瓶颈：考虑降低排序频率（SortConfig::period_ms）或减少点数");
+        }
+        _ => {}
+    }
+
     if avg_frame_time > 16.67 {
         suggestions.push("• 考虑降低点云密度");
     }
@@ -0,0 +1,147 @@
+// 从 transforms.json（NeRF 风格数据集常见的相机位姿文件）加载训练相机，让用户
+// 按键在这些"标准答案视角"之间切换，复现数据集作者拍摄/渲染 ground truth 时的
+// 取景，方便和训练集逐帧比对。
+//
+// 目前只支持 transforms.json 的 OpenGL 相机到世界变换（camera-to-world，4x4，
+// 行主序存储）。COLMAP 的 images.txt（四元数 + 平移，world-to-camera）是另一种
+// 常见来源，格式差异较大，留给后续扩展。
+
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+/// 场景相机标记：`index` 是它在 transforms.json 的 `frames` 数组里的序号。
+#[derive(Component)]
+pub struct SceneCamera {
+    pub index: usize,
+}
+
+/// 已加载的相机位姿集合，以及当前激活的是哪一个（`None` 表示自由环绕相机）。
+#[derive(Resource, Default)]
+pub struct GaussianCameraSet {
+    pub cameras: Vec<Entity>,
+    pub current: Option<usize>,
+}
+
+impl GaussianCameraSet {
+    /// 切到下一个位姿；越过最后一个时回到自由环绕相机（`None`）。
+    pub fn cycle_next(&mut self) -> Option<usize> {
+        self.current = match self.current {
+            None if !self.cameras.is_empty() => Some(0),
+            Some(i) if i + 1 < self.cameras.len() => Some(i + 1),
+            _ => None,
+        };
+        self.current
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TransformsFile {
+    #[serde(default)]
+    camera_angle_x: Option<f32>,
+    frames: Vec<FrameEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FrameEntry {
+    #[serde(default)]
+    file_path: String,
+    transform_matrix: [[f32; 4]; 4],
+}
+
+/// 一个训练视角的位姿：相机的世界变换，以及（文件里提供了的话）水平视场角。
+pub struct CameraPose {
+    pub transform: Transform,
+    pub label: String,
+    pub fov_x: Option<f32>,
+}
+
+/// 解析 transforms.json，返回每一帧的相机位姿。不做任何 ECS spawn，方便调用方
+/// 决定怎么生成实体（也方便脱离 App 单独测试解析逻辑）。
+pub fn parse_transforms_json(path: impl AsRef<Path>) -> Result<Vec<CameraPose>, String> {
+    let path = path.as_ref();
+    let data = fs::read_to_string(path).map_err(|e| format!("读取 {:?} 失败: {}", path, e))?;
+    let parsed: TransformsFile =
+        serde_json::from_str(&data).map_err(|e| format!("解析 {:?} 失败: {}", path, e))?;
+
+    Ok(parsed
+        .frames
+        .iter()
+        .map(|frame| CameraPose {
+            transform: opengl_matrix_to_bevy_transform(&frame.transform_matrix),
+            label: frame.file_path.clone(),
+            fov_x: parsed.camera_angle_x,
+        })
+        .collect())
+}
+
+/// 把 transforms.json 的水平视场角 `fov_x`（弧度）换算成 Bevy `PerspectiveProjection`
+/// 需要的垂直视场角，换算要用到渲染时的宽高比：
+/// `fov_y = 2 * atan(tan(fov_x / 2) / aspect_ratio)`。
+/// 用来复现训练时取景的场景相机应该用数据集原始渲染分辨率的宽高比，而不是
+/// 当前窗口大小——否则窗口一变形，换算出来的取景就和训练集对不上了。
+pub fn fov_y_from_fov_x(fov_x: f32, aspect_ratio: f32) -> f32 {
+    2.0 * ((fov_x / 2.0).tan() / aspect_ratio).atan()
+}
+
+/// `transform_matrix` 是 OpenGL 约定的相机到世界矩阵（行主序存储）。Bevy 相机同样
+/// 是 Y-up、沿本地 -Z 方向看向前方，和 OpenGL 同手性，所以不需要像 OpenCV 导入那样
+/// 做手性翻转，只需要把行主序转成 `Mat4::from_cols_array_2d` 期望的列主序。
+fn opengl_matrix_to_bevy_transform(rows: &[[f32; 4]; 4]) -> Transform {
+    let mut cols = [[0.0f32; 4]; 4];
+    for (r, row) in rows.iter().enumerate() {
+        for (c, value) in row.iter().enumerate() {
+            cols[c][r] = *value;
+        }
+    }
+    Transform::from_matrix(Mat4::from_cols_array_2d(&cols))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_next_wraps_back_to_free_camera() {
+        let mut set = GaussianCameraSet {
+            cameras: vec![Entity::PLACEHOLDER, Entity::PLACEHOLDER],
+            current: None,
+        };
+
+        assert_eq!(set.cycle_next(), Some(0));
+        assert_eq!(set.cycle_next(), Some(1));
+        assert_eq!(set.cycle_next(), None);
+        assert_eq!(set.cycle_next(), Some(0));
+    }
+
+    #[test]
+    fn test_fov_y_from_fov_x_matches_square_aspect_identity() {
+        // 宽高比为1时，水平/垂直视场角相同。
+        let fov_x = std::f32::consts::FRAC_PI_2; // 90度
+        let fov_y = fov_y_from_fov_x(fov_x, 1.0);
+        assert!((fov_y - fov_x).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fov_y_from_fov_x_widescreen_is_narrower_than_horizontal() {
+        // 16:9 宽屏下，垂直视场角应该比水平视场角窄。
+        let fov_x = 60f32.to_radians();
+        let fov_y = fov_y_from_fov_x(fov_x, 16.0 / 9.0);
+        assert!(fov_y < fov_x);
+        assert!((fov_y.to_degrees() - 35.983).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_identity_matrix_maps_to_identity_transform() {
+        let identity = [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ];
+        let transform = opengl_matrix_to_bevy_transform(&identity);
+        assert_eq!(transform.translation, Vec3::ZERO);
+        assert_eq!(transform.rotation, Quat::IDENTITY);
+    }
+}
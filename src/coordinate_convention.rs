@@ -0,0 +1,114 @@
+// 不同工具链对"世界坐标系"的约定不一样（谁朝上、相机朝哪个方向看），3DGS/NeRF
+// 数据集可能来自其中任何一种。之前只认 SHARP 的 OpenCV 风格输出，把修正矩阵硬编码
+// 成 `Transform::from_rotation(Quat::from_rotation_x(PI))`，复制在两个 spawn 点。
+//
+// `CoordinateConvention` 是 `bevy_gaussian_splatting` 的 `CloudSettings`（外部、未
+// vendor 进本仓库的 crate）本该承载的配置——理想情况下它应该是 `CloudSettings` 的
+// 一个字段，由渲染/资源加载流程读取。这里没法改那个 crate，所以退而求其次：在本
+// crate 里提供这个枚举和对应的基变换矩阵，调用方在 spawn 点云时把它转成的
+// `Transform` 和点云自身的 `Transform` 相乘（或者直接当作初始 `Transform`，如果点
+// 云本身不需要额外变换）。auto-center 读的是 spawn 后的 `GlobalTransform`，所以只要
+// 这个基变换被烘焙进了点云实体的 `Transform`，居中计算自动就是对的，不需要额外改动。
+use bevy::prelude::*;
+
+/// 点云/相机位姿数据所采用的坐标系约定。每个取值对应一个把该约定转换到 Bevy 的
+/// Y-up、相机看向本地 -Z 方向的基变换。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoordinateConvention {
+    /// X-right, Y-down, Z-forward（右手系）。SHARP 等基于 OpenCV 管线的重建工具
+    /// 常见输出约定。转换到 Bevy 需要绕 X 轴转 180°（Y、Z 同时翻转）。
+    OpenCv,
+    /// X-right, Y-up, Z-backward（右手系，相机看向本地 -Z）。和 Bevy 同手性，
+    /// 不需要翻转，只是行主序/列主序存储上的差异（见 [`crate::camera_set`]）。
+    OpenGl,
+    /// X-right, Y-forward, Z-up（右手系）。Blender 的世界坐标系约定。转换到 Bevy
+    /// 需要绕 X 轴转 -90°（Y、Z 互换并翻转符号）。
+    Blender,
+    /// X-right, Y-up, Z-backward，和 Bevy 自身一致，不做任何变换。
+    #[default]
+    Bevy,
+}
+
+impl CoordinateConvention {
+    /// 从 `GAUSSIAN_COORD_CONVENTION` 环境变量读取坐标系约定（大小写不敏感，
+    /// 匹配 `opencv`/`opengl`/`blender`/`bevy`）；没设置或值无法识别时回退到
+    /// `OpenCv`——这是本仓库迁移前唯一支持过的约定，保持旧有默认行为不变。
+    pub fn from_env() -> Self {
+        match std::env::var("GAUSSIAN_COORD_CONVENTION") {
+            Ok(value) => match value.to_lowercase().as_str() {
+                "opencv" => CoordinateConvention::OpenCv,
+                "opengl" => CoordinateConvention::OpenGl,
+                "blender" => CoordinateConvention::Blender,
+                "bevy" => CoordinateConvention::Bevy,
+                other => {
+                    error!("❌ 未知的 GAUSSIAN_COORD_CONVENTION 取值: {}，回退到 OpenCv", other);
+                    CoordinateConvention::OpenCv
+                }
+            },
+            Err(_) => CoordinateConvention::OpenCv,
+        }
+    }
+
+    /// 把这个约定下的点/相机数据转换到 Bevy 世界坐标系所需的基变换。
+    pub fn basis_transform(&self) -> Transform {
+        match self {
+            CoordinateConvention::OpenCv => {
+                Transform::from_rotation(Quat::from_rotation_x(std::f32::consts::PI))
+            }
+            CoordinateConvention::OpenGl => Transform::IDENTITY,
+            CoordinateConvention::Blender => {
+                Transform::from_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2))
+            }
+            CoordinateConvention::Bevy => Transform::IDENTITY,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opencv_basis_transform_flips_y_and_z() {
+        let transform = CoordinateConvention::OpenCv.basis_transform();
+        let rotated = transform.rotation * Vec3::Y;
+        assert!((rotated - (-Vec3::Y)).length() < 1e-5);
+        let rotated_z = transform.rotation * Vec3::Z;
+        assert!((rotated_z - (-Vec3::Z)).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_blender_basis_transform_maps_z_up_to_y_up() {
+        let transform = CoordinateConvention::Blender.basis_transform();
+        // Blender 的 Z-up 应该被转成 Bevy 的 Y-up：世界 Z 轴转到 Bevy 的 +Y。
+        let rotated = transform.rotation * Vec3::Z;
+        assert!((rotated - Vec3::Y).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_opengl_and_bevy_basis_transforms_are_identity() {
+        assert_eq!(CoordinateConvention::OpenGl.basis_transform(), Transform::IDENTITY);
+        assert_eq!(CoordinateConvention::Bevy.basis_transform(), Transform::IDENTITY);
+    }
+
+    // 环境变量是进程全局状态；在一个测试函数里顺序跑完所有取值，避免和其它测试
+    // 并行执行时互相踩环境变量。
+    #[test]
+    fn test_from_env_parses_known_values_case_insensitively_and_falls_back_to_opencv() {
+        unsafe { std::env::remove_var("GAUSSIAN_COORD_CONVENTION") };
+        assert_eq!(CoordinateConvention::from_env(), CoordinateConvention::OpenCv);
+
+        for (value, expected) in [
+            ("opencv", CoordinateConvention::OpenCv),
+            ("OpenGL", CoordinateConvention::OpenGl),
+            ("Blender", CoordinateConvention::Blender),
+            ("BEVY", CoordinateConvention::Bevy),
+            ("not-a-real-convention", CoordinateConvention::OpenCv),
+        ] {
+            unsafe { std::env::set_var("GAUSSIAN_COORD_CONVENTION", value) };
+            assert_eq!(CoordinateConvention::from_env(), expected, "value = {value}");
+        }
+
+        unsafe { std::env::remove_var("GAUSSIAN_COORD_CONVENTION") };
+    }
+}
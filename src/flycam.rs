@@ -0,0 +1,148 @@
+// 第一人称"穿梭"相机模式：环绕相机（orbit）只能绕一个目标点转，飞不进室内场景
+// 内部；`GaussianCameraController` 补上标准的 spectator/freecam 控制——锁定并隐藏
+// 鼠标指针，用鼠标移动直接驱动偏航/俯仰，WASD 沿相机本地前/右轴移动，Space/Ctrl
+// 沿世界 Y 轴升降，Shift 加速。是否生效由 `enabled` 字段控制，由调用方（这里是 `main.rs` 的模式
+// 切换按键）负责在环绕/飞行之间切换，并在切换时保留位置与朝向的连续性。
+
+use bevy::input::mouse::MouseMotion;
+use bevy::prelude::*;
+use bevy::window::CursorGrabMode;
+
+#[derive(Component, Clone)]
+pub struct GaussianCameraController {
+    pub enabled: bool,
+    /// 弧度/像素
+    pub sensitivity: f32,
+    /// 单位/秒
+    pub speed: f32,
+    pub run_multiplier: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Default for GaussianCameraController {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sensitivity: 0.003,
+            speed: 3.0,
+            run_multiplier: 3.0,
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+}
+
+impl GaussianCameraController {
+    /// 切入飞行模式时调用：从当前相机朝向取 yaw/pitch，保证这一帧视角不跳变。
+    pub fn sync_from_transform(&mut self, transform: &Transform) {
+        let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        self.yaw = yaw;
+        self.pitch = pitch;
+    }
+}
+
+/// 飞行模式下的输入处理：鼠标驱动视角，WASD 沿本地前/右轴移动，Space/Ctrl 沿世界 Y 轴升降。
+/// `controller.enabled == false` 时只清空鼠标事件（避免退出飞行模式后残留的
+/// 鼠标位移在下一次进入时被当成一次性大跳变消费掉），不做任何其它事情。
+pub fn flycam_controls(
+    mut mouse_motion: MessageReader<MouseMotion>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut windows: Query<&mut Window>,
+    mut query: Query<(&mut Transform, &mut GaussianCameraController)>,
+    time: Res<Time>,
+) {
+    let Ok((mut transform, mut controller)) = query.single_mut() else {
+        return;
+    };
+
+    if !controller.enabled {
+        mouse_motion.clear();
+        return;
+    }
+
+    let dt = time.delta_secs();
+
+    let mut mouse_delta = Vec2::ZERO;
+    for ev in mouse_motion.read() {
+        mouse_delta += ev.delta;
+    }
+
+    controller.yaw -= mouse_delta.x * controller.sensitivity;
+    controller.pitch = (controller.pitch - mouse_delta.y * controller.sensitivity).clamp(-1.54, 1.54);
+    transform.rotation = Quat::from_euler(EulerRot::YXZ, controller.yaw, controller.pitch, 0.0);
+
+    let mut speed = controller.speed;
+    if keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        speed *= controller.run_multiplier;
+    }
+
+    let forward = transform.forward();
+    let right = transform.right();
+    let mut movement = Vec3::ZERO;
+    if keyboard.pressed(KeyCode::KeyW) {
+        movement += *forward;
+    }
+    if keyboard.pressed(KeyCode::KeyS) {
+        movement -= *forward;
+    }
+    if keyboard.pressed(KeyCode::KeyD) {
+        movement += *right;
+    }
+    if keyboard.pressed(KeyCode::KeyA) {
+        movement -= *right;
+    }
+    if keyboard.pressed(KeyCode::Space) {
+        movement += Vec3::Y;
+    }
+    if keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        movement -= Vec3::Y;
+    }
+
+    if movement != Vec3::ZERO {
+        transform.translation += movement.normalize() * speed * dt;
+    }
+
+    for mut window in windows.iter_mut() {
+        window.cursor_options.grab_mode = CursorGrabMode::Locked;
+        window.cursor_options.visible = false;
+    }
+}
+
+/// 离开飞行模式时把鼠标指针还给用户。
+pub fn release_cursor(mut windows: Query<&mut Window>) {
+    for mut window in windows.iter_mut() {
+        window.cursor_options.grab_mode = CursorGrabMode::None;
+        window.cursor_options.visible = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_from_transform_recovers_identity_yaw_pitch() {
+        let mut controller = GaussianCameraController {
+            yaw: 1.0,
+            pitch: 1.0,
+            ..GaussianCameraController::default()
+        };
+        controller.sync_from_transform(&Transform::IDENTITY);
+        assert!(controller.yaw.abs() < 1e-5);
+        assert!(controller.pitch.abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_sync_from_transform_matches_looking_at_rotation() {
+        let mut controller = GaussianCameraController::default();
+        let transform = Transform::from_xyz(0.0, 0.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y);
+        controller.sync_from_transform(&transform);
+
+        // 同步后按 yaw/pitch 重建的朝向应该还原出同一个本地 -Z 前向。
+        let rebuilt = Quat::from_euler(EulerRot::YXZ, controller.yaw, controller.pitch, 0.0);
+        let original_forward = transform.rotation * Vec3::NEG_Z;
+        let rebuilt_forward = rebuilt * Vec3::NEG_Z;
+        assert!((original_forward - rebuilt_forward).length() < 1e-5);
+    }
+}
@@ -1,9 +1,14 @@
 use bevy::prelude::*;
+use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use serde::Deserialize;
 
+use crate::ply_backend::ChunkedHttpBackend;
+use crate::ply_cache::PlyCacheManager;
+
 /// 上传状态
 #[derive(Debug, Clone, PartialEq)]
 pub enum UploadStatus {
@@ -21,6 +26,9 @@ pub enum UploadStatus {
 pub struct ImageUploadState {
     pub status: Arc<Mutex<UploadStatus>>,
     pub server_url: String,
+    /// 当前任务的取消标记。每次开始新任务都会替换成一个新的 `Arc<AtomicBool>`，
+    /// 旧任务的标记先被置位（单飞：新上传会取消上一个还在跑的上传）。
+    active_cancel_flag: Arc<Mutex<Arc<AtomicBool>>>,
 }
 
 impl Default for ImageUploadState {
@@ -28,6 +36,7 @@ impl Default for ImageUploadState {
         Self {
             status: Arc::new(Mutex::new(UploadStatus::Idle)),
             server_url: "http://192.168.31.164:8000".to_string(),
+            active_cancel_flag: Arc::new(Mutex::new(Arc::new(AtomicBool::new(false)))),
         }
     }
 }
@@ -40,10 +49,26 @@ impl ImageUploadState {
     pub fn set_status(&self, status: UploadStatus) {
         *self.status.lock().unwrap() = status;
     }
+
+    /// 开始一个新任务：取消上一个还在跑的任务（单飞），返回新任务要检查的取消标记。
+    fn begin_job(&self) -> Arc<AtomicBool> {
+        let mut current = self.active_cancel_flag.lock().unwrap();
+        current.store(true, Ordering::SeqCst); // 取消上一个任务
+        let fresh = Arc::new(AtomicBool::new(false));
+        *current = Arc::clone(&fresh);
+        fresh
+    }
+
+    /// 取消当前正在进行的任务（如果有的话）。供 UI 按钮调用。
+    pub fn cancel(&self) {
+        self.active_cancel_flag.lock().unwrap().store(true, Ordering::SeqCst);
+    }
 }
 
 /// 触发文件选择对话框
 pub fn trigger_file_dialog(upload_state: ImageUploadState) {
+    let cancel_flag = upload_state.begin_job();
+
     std::thread::spawn(move || {
         upload_state.set_status(UploadStatus::SelectingFile);
 
@@ -55,7 +80,7 @@ pub fn trigger_file_dialog(upload_state: ImageUploadState) {
 
         if let Some(path) = file {
             info!("📁 选择了文件: {:?}", path);
-            upload_and_process(upload_state, path);
+            upload_and_process(upload_state, path, cancel_flag);
         } else {
             info!("❌ 取消选择文件");
             upload_state.set_status(UploadStatus::Idle);
@@ -69,11 +94,125 @@ struct DownloadInfo {
     file_size: usize,
     chunk_size: usize,
     num_chunks: usize,
+    #[allow(dead_code)]
     filename: String,
+    /// 可选的整文件摘要（服务端支持时返回），用于重组后做一次整体校验。
+    #[serde(default)]
+    file_sha256: Option<String>,
+}
+
+/// 单个分块最多尝试次数（含首次请求，即最多重试 `CHUNK_MAX_ATTEMPTS - 1` 次），用完后该分块判定为下载失败。
+const CHUNK_MAX_ATTEMPTS: u32 = 4;
+/// 指数退避的基础等待时间，第 n 次重试等待 `CHUNK_RETRY_BASE_DELAY * 2^(n-1)`。
+const CHUNK_RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+
+/// 本次任务的分块缓存目录：中断后重新下载同一个 job 时，已经落盘的分块会被复用。
+fn chunk_cache_dir() -> PathBuf {
+    PathBuf::from("cache/chunks")
+}
+
+fn chunk_part_path(job_id: &str, chunk_id: usize) -> PathBuf {
+    chunk_cache_dir().join(format!("{}.{}.part", job_id, chunk_id))
 }
 
-/// 并行下载PLY文件
-fn download_ply_parallel(server_url: &str, job_id: &str) -> Result<Vec<u8>, String> {
+/// 第 `attempt` 次重试（1-based）前应该等待多久：`CHUNK_RETRY_BASE_DELAY * 2^(attempt-1)`。
+fn backoff_duration(attempt: u32) -> Duration {
+    CHUNK_RETRY_BASE_DELAY * 2u32.pow(attempt - 1)
+}
+
+/// 下载单个分块，失败时按指数退避重试；成功后把分块落盘到 `<job_id>.<chunk_id>.part`，
+/// 这样中断后重新下载同一个 job 可以跳过已经取到的分块。
+fn fetch_chunk_with_retry(
+    server_url: &str,
+    job_id: &str,
+    chunk_id: usize,
+    chunk_size: usize,
+    file_size: usize,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Result<Vec<u8>, String> {
+    let chunk_url = format!("{}/api/download_chunk/{}/{}", server_url, job_id, chunk_id);
+    let range_start = chunk_id * chunk_size;
+    let range_end = (range_start + chunk_size).min(file_size).saturating_sub(1);
+    let expected_len = range_end + 1 - range_start;
+
+    let part_path = chunk_part_path(job_id, chunk_id);
+    if let Ok(data) = fs::read(&part_path) {
+        if data.len() == expected_len {
+            info!("♻️  块 {} 复用已缓存的分块 ({} bytes)", chunk_id, data.len());
+            return Ok(data);
+        }
+        error!(
+            "⚠️  块 {} 的缓存分块大小不符（缓存 {} bytes, 预期 {} bytes），丢弃重新下载",
+            chunk_id, data.len(), expected_len
+        );
+        let _ = fs::remove_file(&part_path);
+    }
+
+    let mut last_err = String::new();
+    for attempt in 1..=CHUNK_MAX_ATTEMPTS {
+        if cancel_flag.load(Ordering::SeqCst) {
+            return Err("cancelled".to_string());
+        }
+
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+
+        let result = client
+            .get(&chunk_url)
+            .header("Range", format!("bytes={}-{}", range_start, range_end))
+            .send();
+
+        match result {
+            Ok(response) if response.status().is_success() => match response.bytes() {
+                Ok(data) => {
+                    let data = data.to_vec();
+                    if let Err(e) = persist_chunk(&part_path, &data) {
+                        error!("⚠️  块 {} 落盘失败（不影响本次使用）: {}", chunk_id, e);
+                    }
+                    info!("✅ 块 {} 下载完成 ({} bytes)", chunk_id, data.len());
+                    return Ok(data);
+                }
+                Err(e) => last_err = format!("读取响应失败: {}", e),
+            },
+            Ok(response) => last_err = format!("HTTP {}", response.status()),
+            Err(e) => last_err = format!("请求失败: {}", e),
+        }
+
+        if attempt < CHUNK_MAX_ATTEMPTS {
+            let backoff = backoff_duration(attempt);
+            error!(
+                "❌ 块 {} 第 {} 次尝试失败（{}），{:?} 后重试",
+                chunk_id, attempt, last_err, backoff
+            );
+            std::thread::sleep(backoff);
+        }
+    }
+
+    Err(format!(
+        "块 {} 下载失败，已尝试 {} 次: {}",
+        chunk_id, CHUNK_MAX_ATTEMPTS, last_err
+    ))
+}
+
+/// 原子落盘：先写临时文件再 rename，避免进程中途被杀死时留下半截分块。
+fn persist_chunk(path: &std::path::Path, data: &[u8]) -> Result<(), std::io::Error> {
+    fs::create_dir_all(chunk_cache_dir())?;
+    let tmp_path = path.with_extension("part.tmp");
+    fs::write(&tmp_path, data)?;
+    fs::rename(&tmp_path, path)
+}
+
+/// 并行下载PLY文件，按分块落盘缓存并支持断点续传：中断后重新调用会跳过已经
+/// 成功拿到的分块，每个分块失败时按指数退避重试，而不是让整个任务失败。
+/// 每个分块在每次尝试（包括重试）之前检查 `cancel_flag`：取消后不再发起新的请求或
+/// 重试，但已经在途的请求会跑完，本函数等所有分块线程结束后才返回 `Err("cancelled")`。
+fn download_ply_parallel(
+    server_url: &str,
+    job_id: &str,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Result<Vec<u8>, String> {
     // 1. 获取下载信息
     let info_url = format!("{}/api/download_info/{}", server_url, job_id);
     let client = reqwest::blocking::Client::builder()
@@ -90,7 +229,7 @@ fn download_ply_parallel(server_url: &str, job_id: &str) -> Result<Vec<u8>, Stri
 
     info!("📊 文件信息: {} bytes, {} 个块", info.file_size, info.num_chunks);
 
-    // 2. 并行下载所有块
+    // 2. 并行下载所有块（各自独立重试/落盘）
     let chunks: Arc<Mutex<Vec<Option<Vec<u8>>>>> = Arc::new(Mutex::new(vec![None; info.num_chunks]));
     let mut handles = vec![];
 
@@ -98,30 +237,17 @@ fn download_ply_parallel(server_url: &str, job_id: &str) -> Result<Vec<u8>, Stri
         let server_url = server_url.to_string();
         let job_id = job_id.to_string();
         let chunks = Arc::clone(&chunks);
+        let chunk_size = info.chunk_size;
+        let file_size = info.file_size;
+        let cancel_flag = Arc::clone(cancel_flag);
 
         let handle = std::thread::spawn(move || {
-            let chunk_url = format!("{}/api/download_chunk/{}/{}", server_url, job_id, chunk_id);
-            let client = reqwest::blocking::Client::builder()
-                .timeout(std::time::Duration::from_secs(30))
-                .build()
-                .unwrap();
-
-            match client.get(&chunk_url).send() {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        match response.bytes() {
-                            Ok(data) => {
-                                let mut chunks = chunks.lock().unwrap();
-                                chunks[chunk_id] = Some(data.to_vec());
-                                info!("✅ 块 {} 下载完成 ({} bytes)", chunk_id, data.len());
-                            }
-                            Err(e) => error!("❌ 块 {} 读取失败: {}", chunk_id, e),
-                        }
-                    } else {
-                        error!("❌ 块 {} 下载失败: {}", chunk_id, response.status());
-                    }
+            match fetch_chunk_with_retry(&server_url, &job_id, chunk_id, chunk_size, file_size, &cancel_flag) {
+                Ok(data) => {
+                    let mut chunks = chunks.lock().unwrap();
+                    chunks[chunk_id] = Some(data);
                 }
-                Err(e) => error!("❌ 块 {} 请求失败: {}", chunk_id, e),
+                Err(e) => error!("❌ {}", e),
             }
         });
 
@@ -133,6 +259,10 @@ fn download_ply_parallel(server_url: &str, job_id: &str) -> Result<Vec<u8>, Stri
         let _ = handle.join();
     }
 
+    if cancel_flag.load(Ordering::SeqCst) {
+        return Err("cancelled".to_string());
+    }
+
     // 4. 重组数据
     let chunks = chunks.lock().unwrap();
     let mut result = Vec::with_capacity(info.file_size);
@@ -152,11 +282,67 @@ fn download_ply_parallel(server_url: &str, job_id: &str) -> Result<Vec<u8>, Stri
         ));
     }
 
+    if let Some(expected) = &info.file_sha256 {
+        let actual = sha256_hex(&result);
+        if actual != *expected {
+            return Err(format!(
+                "整文件校验失败: 预期 {}, 实际 {}",
+                expected, actual
+            ));
+        }
+    }
+
+    // 5. 整个文件已经验证完整，分块缓存不再需要
+    for chunk_id in 0..info.num_chunks {
+        let _ = fs::remove_file(chunk_part_path(job_id, chunk_id));
+    }
+
     Ok(result)
 }
 
+/// 公开包装：不带取消能力地取回某个 job 的完整 PLY 字节。供 [`crate::ply_backend::ChunkedHttpBackend`]
+/// 这样的调用方使用，不需要关心这里内部的分块/重试/续传细节。
+pub fn fetch_all_chunks(server_url: &str, job_id: &str) -> Result<Vec<u8>, String> {
+    let never_cancelled = Arc::new(AtomicBool::new(false));
+    download_ply_parallel(server_url, job_id, &never_cancelled)
+}
+
+/// 同上，但带取消能力。供 [`crate::ply_backend::ChunkedHttpBackend::with_cancel_flag`]
+/// 使用，让经由 `PlyCacheManager::get_or_fetch` 发起的下载仍然能被用户取消。
+pub fn fetch_all_chunks_cancellable(
+    server_url: &str,
+    job_id: &str,
+    cancel_flag: &Arc<AtomicBool>,
+) -> Result<Vec<u8>, String> {
+    download_ply_parallel(server_url, job_id, cancel_flag)
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    let bytes = hasher.finalize();
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
+
+/// 任务在阶段边界检查是否已被取消（新上传单飞替换，或用户主动调用 `cancel()`）。
+/// 取消时把状态收回 `Idle`（而不是 `Error`，取消不是失败）并返回 `true`。
+fn bail_if_cancelled(upload_state: &ImageUploadState, cancel_flag: &Arc<AtomicBool>) -> bool {
+    if cancel_flag.load(Ordering::SeqCst) {
+        info!("🛑 任务已取消");
+        upload_state.set_status(UploadStatus::Idle);
+        true
+    } else {
+        false
+    }
+}
+
 /// 上传图片并处理
-fn upload_and_process(upload_state: ImageUploadState, image_path: PathBuf) {
+fn upload_and_process(upload_state: ImageUploadState, image_path: PathBuf, cancel_flag: Arc<AtomicBool>) {
     let start_time = Instant::now();
 
     // 读取图片文件
@@ -173,6 +359,10 @@ fn upload_and_process(upload_state: ImageUploadState, image_path: PathBuf) {
         }
     };
 
+    if bail_if_cancelled(&upload_state, &cancel_flag) {
+        return;
+    }
+
     info!("📤 开始上传图片 ({:.2} MB)...", image_data.len() as f32 / 1_000_000.0);
     upload_state.set_status(UploadStatus::Uploading { progress: 0.5 });
 
@@ -243,12 +433,25 @@ fn upload_and_process(upload_state: ImageUploadState, image_path: PathBuf) {
         }
     };
 
+    if bail_if_cancelled(&upload_state, &cancel_flag) {
+        return;
+    }
+
     info!("✅ SHARP推理完成，开始并行下载PLY...");
     upload_state.set_status(UploadStatus::Downloading { progress: 0.0 });
 
-    // 使用并行下载
-    let ply_data = match download_ply_parallel(&upload_state.server_url, &job_response.job_id) {
+    // 经由内容寻址缓存取回：同一个 job_id 之前已经下载过（比如应用重启后重试）
+    // 时直接命中缓存，不用再走一遍分块下载。真正的网络下载仍然是带取消能力、
+    // 按分块重试/续传的 `download_ply_parallel`，只是外面包了一层缓存。
+    let cache = PlyCacheManager::new("cache/ply");
+    let backend = ChunkedHttpBackend::new(upload_state.server_url.clone())
+        .with_cancel_flag(Arc::clone(&cancel_flag));
+    let ply_data = match cache.get_or_fetch(&job_response.job_id, &backend) {
         Ok(data) => data,
+        Err(e) if e == "cancelled" => {
+            bail_if_cancelled(&upload_state, &cancel_flag);
+            return;
+        }
         Err(e) => {
             error!("❌ 并行下载失败: {}", e);
             upload_state.set_status(UploadStatus::Error {
@@ -271,6 +474,13 @@ fn upload_and_process(upload_state: ImageUploadState, image_path: PathBuf) {
         return;
     }
 
+    // `get_or_fetch` 只在分块下载过程中检查取消；缓存命中、重组校验、写缓存以及上面
+    // 写 assets 文件这几步都不检查。任务在这期间被取消（单飞替换或 `cancel()`）时，
+    // 这里不设 Completed，而是由 `bail_if_cancelled` 把状态收回 Idle。
+    if bail_if_cancelled(&upload_state, &cancel_flag) {
+        return;
+    }
+
     let total_time = start_time.elapsed().as_secs_f32();
     info!("🎉 完成！总耗时: {:.2}秒", total_time);
     info!("📁 PLY文件已保存到: {:?}", output_path);
@@ -280,3 +490,69 @@ fn upload_and_process(upload_state: ImageUploadState, image_path: PathBuf) {
         total_time,
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_part_path_format() {
+        assert_eq!(
+            chunk_part_path("job-123", 7),
+            PathBuf::from("cache/chunks/job-123.7.part")
+        );
+    }
+
+    #[test]
+    fn test_backoff_duration_doubles_each_attempt() {
+        assert_eq!(backoff_duration(1), CHUNK_RETRY_BASE_DELAY);
+        assert_eq!(backoff_duration(2), CHUNK_RETRY_BASE_DELAY * 2);
+        assert_eq!(backoff_duration(3), CHUNK_RETRY_BASE_DELAY * 4);
+        assert_eq!(backoff_duration(4), CHUNK_RETRY_BASE_DELAY * 8);
+    }
+
+    #[test]
+    fn test_begin_job_cancels_previous_flag_single_flight() {
+        let state = ImageUploadState::default();
+
+        let first = state.begin_job();
+        assert!(!first.load(Ordering::SeqCst));
+
+        // 开始第二个任务：第一个任务的标记应该被取消，且是与新标记不同的对象。
+        let second = state.begin_job();
+        assert!(first.load(Ordering::SeqCst));
+        assert!(!second.load(Ordering::SeqCst));
+        assert!(!Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_bail_if_cancelled_resets_status_to_idle_and_reports_true() {
+        let state = ImageUploadState::default();
+        let flag = state.begin_job();
+        state.set_status(UploadStatus::Downloading { progress: 0.5 });
+
+        flag.store(true, Ordering::SeqCst);
+        assert!(bail_if_cancelled(&state, &flag));
+        assert_eq!(state.get_status(), UploadStatus::Idle);
+    }
+
+    #[test]
+    fn test_bail_if_cancelled_leaves_status_untouched_when_not_cancelled() {
+        let state = ImageUploadState::default();
+        let flag = state.begin_job();
+        state.set_status(UploadStatus::Downloading { progress: 0.5 });
+
+        assert!(!bail_if_cancelled(&state, &flag));
+        assert_eq!(state.get_status(), UploadStatus::Downloading { progress: 0.5 });
+    }
+
+    #[test]
+    fn test_cancel_sets_current_active_flag() {
+        let state = ImageUploadState::default();
+        let current = state.begin_job();
+        assert!(!current.load(Ordering::SeqCst));
+
+        state.cancel();
+        assert!(current.load(Ordering::SeqCst));
+    }
+}
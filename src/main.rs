@@ -1,6 +1,5 @@
 use bevy::prelude::*;
-use bevy::camera::primitives::Aabb;
-use bevy::input::mouse::{MouseButton, MouseMotion, MouseScrollUnit, MouseWheel};
+use std::path::Path;
 use bevy_gaussian_splatting::{
     CloudSettings,
     GaussianCamera,
@@ -15,76 +14,37 @@ use ply_cache::PlyCacheManager;
 mod image_uploader;
 use image_uploader::{ImageUploadState, UploadStatus, trigger_file_dialog};
 
-#[derive(Component)]
-struct MainCloud;
+mod ply_backend;
+use ply_backend::PlyBackend;
 
-#[derive(Component)]
-struct MainCamera;
+mod camera_set;
+use camera_set::{fov_y_from_fov_x, parse_transforms_json, GaussianCameraSet, SceneCamera};
 
-#[derive(Resource, Debug, Clone)]
-struct OrbitState {
-    target: Vec3,
-    distance: f32,
-    yaw: f32,
-    pitch: f32,
-    pan_speed: f32,
-    rotate_speed: f32,
-    zoom_speed: f32,
-    mouse_rotate_sensitivity: f32,
-    mouse_pan_sensitivity: f32,
-    mouse_zoom_sensitivity: f32,
-    has_auto_centered: bool,
-}
+mod skybox;
+use skybox::{attach_skybox_when_loaded, GaussianSkybox};
 
-/// 输入事件节流器：限制输入处理频率，避免事件堆积导致延迟
-/// 类似摄像头项目中的"只在有新帧时解码"策略
-#[derive(Resource)]
-struct InputThrottle {
-    last_update: f32,
-    min_interval: f32, // 16.67ms = 60fps
-}
+mod flycam;
+use flycam::{flycam_controls, release_cursor, GaussianCameraController};
 
-impl Default for InputThrottle {
-    fn default() -> Self {
-        Self {
-            last_update: 0.0,
-            min_interval: 1.0 / 60.0, // 60 FPS
-        }
-    }
-}
+mod orbit_camera;
+use orbit_camera::{GaussianOrbitCameraPlugin, OrbitCamera, OrbitCameraEnabled, OrbitCameraTarget};
 
-impl Default for OrbitState {
-    fn default() -> Self {
-        Self {
-            target: Vec3::ZERO,
-            distance: 5.0,
-            yaw: 0.0,
-            pitch: 0.0,
-            // Pan speed scales by distance so it feels consistent at different zoom levels.
-            pan_speed: 1.0,
-            rotate_speed: 1.2, // rad/s
-            zoom_speed: 6.0,   // units/s
-            mouse_rotate_sensitivity: 0.005, // rad/pixel
-            mouse_pan_sensitivity: 0.002,    // world units per pixel per distance
-            mouse_zoom_sensitivity: 0.4,     // world units per scroll "line"
-            has_auto_centered: false,
-        }
-    }
-}
+mod coordinate_convention;
+use coordinate_convention::CoordinateConvention;
 
-impl OrbitState {
-    fn camera_transform(&self) -> Transform {
-        let rot = Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0);
-        let pos = self.target + rot * Vec3::new(0.0, 0.0, self.distance.max(0.05));
-        Transform::from_translation(pos).looking_at(self.target, Vec3::Y)
-    }
-}
+#[derive(Component)]
+struct MainCloud;
+
+#[derive(Component)]
+struct MainCamera;
+
+/// PLY 缓存默认容量预算（2GB），超出后按 LRU 淘汰最久未访问的条目。
+const DEFAULT_PLY_CACHE_MAX_BYTES: u64 = 2 * 1024 * 1024 * 1024;
 
 fn main() {
     App::new()
-        .insert_resource(OrbitState::default())
-        .insert_resource(InputThrottle::default())
         .insert_resource(ImageUploadState::default())
+        .insert_resource(GaussianCameraSet::default())
         // 优化排序频率：降低GPU占用的关键
         // 默认1000ms排序一次，增加到2000ms可显著降低GPU负载
         // 对视觉影响很小（除非快速旋转相机）
@@ -114,26 +74,37 @@ fn main() {
             })
         )
         .add_plugins(GaussianSplattingPlugin)
+        .add_plugins(GaussianOrbitCameraPlugin)
         .add_systems(Startup, setup)
         .add_systems(Update, (
-            auto_center_orbit_target,
-            orbit_camera_controls,
             handle_import_key,
             update_status_display,
             handle_upload_completion,
+            handle_camera_cycle_key,
+            toggle_flycam_mode,
+            flycam_controls,
         ).chain())
+        .add_systems(Update, attach_skybox_when_loaded)
         .run();
 }
 
 fn setup(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
-    orbit: Res<OrbitState>,
+    mut camera_set: ResMut<GaussianCameraSet>,
 ) {
     info!("🎉 Microscope 3DGS Viewer - Optimized!");
 
     // 初始化 PLY 缓存管理器
-    let cache = PlyCacheManager::new("cache/ply");
+    let mut cache = PlyCacheManager::new("cache/ply");
+
+    // 容量预算：不设的话 evict_if_needed 永远是 no-op，缓存会无限增长。默认 2GB，
+    // 可以用 GAUSSIAN_PLY_CACHE_MAX_BYTES（字节数）覆盖。
+    let cache_budget = std::env::var("GAUSSIAN_PLY_CACHE_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_PLY_CACHE_MAX_BYTES);
+    cache.set_max_total_bytes(cache_budget);
 
     // 显示缓存统计
     if let Ok(stats) = cache.cache_stats() {
@@ -149,9 +120,17 @@ fn setup(
 
     // 加载新生成的PLY文件（从Bevy logo生成）
     // 可以切换为剪枝版本测试: generated_pruned.ply (50%) 或 generated_pruned_35.ply (35%)
-    let ply_file = "generated_pruned.ply";  // 使用剪枝后的版本
+    // 如果设置了 GAUSSIAN_PLY_SOURCE/GAUSSIAN_PLY_KEY，改从外部数据源（本地目录/
+    // HTTP/S3/SHARP 推理服务器）经缓存取回一份点云，而不是加载仓库自带的默认文件——
+    // 这样用户可以直接把 viewer 指向一个模型库/S3 桶使用，不需要跑自建推理服务器。
+    let ply_file = resolve_external_ply_source(&cache)
+        .unwrap_or_else(|| "generated_pruned.ply".to_string()); // 使用剪枝后的版本
     info!("Loading PLY file: {} (LightGaussian pruned)", ply_file);
 
+    // 数据集采用哪种坐标系约定由 GAUSSIAN_COORD_CONVENTION 决定（默认 OpenCv，
+    // 匹配 SHARP 等基于 OpenCV 管线的重建工具），而不是写死一种转换。
+    let coord_convention = CoordinateConvention::from_env();
+
     commands.spawn((
         PlanarGaussian3dHandle(asset_server.load(ply_file)),
         // 优化的CloudSettings：在不损失质量的前提下降低GPU占用
@@ -165,24 +144,78 @@ fn setup(
             ..default()
         },
         // Needed so Bevy's visibility/extraction systems (and gaussian renderer) can see this entity.
-        // SHARP's output is effectively in an OpenCV-like camera coordinate system (Y-down, Z-forward).
-        // Rotate it into Bevy's Y-up, Z-back convention so the initial view matches the input image.
-        Transform::from_rotation(Quat::from_rotation_x(std::f32::consts::PI)),
+        coord_convention.basis_transform(),
         Visibility::default(),
         MainCloud,
+        OrbitCameraTarget,
         Name::new("gaussian_cloud"),
     ));
 
     // 添加相机
-    commands.spawn((
-        // Marks this camera as a gaussian-splatting camera (required by bevy_gaussian_splatting).
-        GaussianCamera { warmup: true },
-        Camera3d::default(),
-        // Metal优化3: 禁用MSAA（3DGS不需要，且在Metal上是tile带宽灾难）
-        Msaa::Off,
-        MainCamera,
-        orbit.camera_transform(),
-    ));
+    let orbit = OrbitCamera::default();
+    let main_camera = commands
+        .spawn((
+            // Marks this camera as a gaussian-splatting camera (required by bevy_gaussian_splatting).
+            GaussianCamera { warmup: true },
+            Camera3d::default(),
+            // Metal优化3: 禁用MSAA（3DGS不需要，且在Metal上是tile带宽灾难）
+            Msaa::Off,
+            MainCamera,
+            GaussianCameraController::default(),
+            orbit.camera_transform(),
+            orbit,
+        ))
+        .id();
+
+    // 如果设置了 GAUSSIAN_SKYBOX_IMAGE，给主相机挂一张天空盒，而不是让
+    // `attach_skybox_when_loaded` 永远找不到 `GaussianSkybox` 组件可接。
+    if let Some(skybox) = resolve_skybox(&asset_server) {
+        commands.entity(main_camera).insert(skybox);
+    }
+
+    // 如果数据集带有 transforms.json（NeRF 风格的训练相机位姿），为每一帧各生成
+    // 一个禁用的场景相机，供 `handle_camera_cycle_key` 按键切换，复现原始取景。
+    let transforms_path = "assets/transforms.json";
+    if Path::new(transforms_path).exists() {
+        match parse_transforms_json(transforms_path) {
+            Ok(poses) => {
+                info!("📷 从 {} 加载了 {} 个训练相机位姿", transforms_path, poses.len());
+                for (index, pose) in poses.into_iter().enumerate() {
+                    let entity = commands
+                        .spawn((
+                            GaussianCamera { warmup: true },
+                            Camera3d::default(),
+                            Camera {
+                                is_active: false,
+                                ..default()
+                            },
+                            Msaa::Off,
+                            pose.transform,
+                            SceneCamera { index },
+                            Name::new(format!("scene_camera_{}", pose.label)),
+                        ))
+                        .id();
+
+                    // transforms.json 的 camera_angle_x 是训练渲染时的水平视场角；
+                    // 不套用它的话每台场景相机都用 Bevy 默认 FOV，复现出来的取景会和
+                    // ground truth 对不上。换算到垂直 FOV 要用训练渲染的宽高比，这里
+                    // 假定和本窗口一致（1280x720）。
+                    if let Some(fov_x) = pose.fov_x {
+                        let fov_y = fov_y_from_fov_x(fov_x, 1280.0 / 720.0);
+                        commands.entity(entity).insert(Projection::Perspective(PerspectiveProjection {
+                            fov: fov_y,
+                            ..default()
+                        }));
+                    }
+
+                    camera_set.cameras.push(entity);
+                }
+            }
+            Err(e) => {
+                error!("❌ 解析 {} 失败: {}", transforms_path, e);
+            }
+        }
+    }
 
     // 添加光源
     commands.spawn((
@@ -198,6 +231,8 @@ fn setup(
     info!("");
     info!("🎮 Controls:");
     info!("  I:                 导入图片生成3DGS");
+    info!("  C:                 在训练相机视角/自由环绕相机间切换");
+    info!("  Tab:               在环绕相机/第一人称飞行相机间切换");
     info!("  Ctrl + Left Drag:  Rotate");
     info!("  Ctrl + Right Drag: Pan");
     info!("  Ctrl + Wheel:      Zoom");
@@ -222,163 +257,73 @@ fn setup(
     info!("  预计GPU占用降低: 50-70%");
 }
 
-fn auto_center_orbit_target(
-    mut orbit: ResMut<OrbitState>,
-    cloud_q: Query<(&Aabb, &GlobalTransform), With<MainCloud>>,
-) {
-    if orbit.has_auto_centered {
-        return;
-    }
-
-    let Ok((aabb, cloud_gt)) = cloud_q.single() else {
-        return;
-    };
-
-    // Center the orbit on the cloud once we have its bounds, and pick a reasonable distance.
-    let center_world = cloud_gt.affine().transform_point3a(aabb.center);
-    let center_world: Vec3 = center_world.into();
-
-    // Initial view: center the cloud in-frame, but keep the SHARP->Bevy axis fix above so the
-    // "front" view matches the input image direction (instead of being mirrored/back-facing).
-    orbit.yaw = 0.0;
-    orbit.pitch = 0.0;
-    orbit.target = center_world;
-
-    let radius = aabb.half_extents.length().max(0.05);
-    orbit.distance = (radius * 3.0).max(0.5);
-
-    orbit.has_auto_centered = true;
-}
-
-fn orbit_camera_controls(
-    keyboard: Res<ButtonInput<KeyCode>>,
-    mouse_buttons: Res<ButtonInput<MouseButton>>,
-    mut mouse_motion: MessageReader<MouseMotion>,
-    mut mouse_wheel: MessageReader<MouseWheel>,
-    mut orbit: ResMut<OrbitState>,
-    mut throttle: ResMut<InputThrottle>,
-    mut camera_query: Query<&mut Transform, With<MainCamera>>,
-    time: Res<Time>,
-) {
-    let Ok(mut camera_transform) = camera_query.single_mut() else {
-        return;
-    };
-
-    let dt = time.delta_secs();
-    let current_time = time.elapsed_secs();
-
-    // 输入节流：限制处理频率到 60fps，避免事件堆积
-    // 类似摄像头项目中"只在有新帧时解码"的策略
-    let should_process_mouse = current_time - throttle.last_update >= throttle.min_interval;
-
-    if !should_process_mouse {
-        // 清空事件，避免堆积
-        mouse_motion.clear();
-        mouse_wheel.clear();
-    } else {
-        throttle.last_update = current_time;
-    }
-
-    let ctrl_pressed = keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
-
-    if keyboard.just_pressed(KeyCode::KeyR) {
-        *orbit = OrbitState::default();
-        orbit.has_auto_centered = false; // allow re-centering once bounds exist
-    }
-
-    // Mouse controls (Ctrl + mouse), similar to many DCC / drawing tools.
-    let mut motion = Vec2::ZERO;
-    if should_process_mouse {
-        for ev in mouse_motion.read() {
-            motion += ev.delta;
+/// 如果设置了 `GAUSSIAN_PLY_SOURCE`（和 `GAUSSIAN_PLY_KEY`），从外部数据源取回
+/// 初始点云，经 `cache` 去重缓存后落盘到 `assets/` 下，返回 Bevy `AssetServer`
+/// 能直接 `load()` 的相对路径；没设置、取回失败或 key 缺失时返回 `None`，调用方
+/// 回退到仓库自带的默认 PLY 文件。
+///
+/// `GAUSSIAN_PLY_SOURCE` 格式为 `<backend>:<config>`：
+///   - `local:/path/to/models`                        -> [`ply_backend::LocalFileBackend`]
+///   - `http:https://models.example.com`              -> [`ply_backend::HttpBackend`]
+///   - `s3:https://s3.example.com,my-bucket,us-east-1` -> [`ply_backend::S3Backend`]
+///   - `chunked:https://sharp-server.example.com`      -> [`ply_backend::ChunkedHttpBackend`]
+fn resolve_external_ply_source(cache: &PlyCacheManager) -> Option<String> {
+    let spec = std::env::var("GAUSSIAN_PLY_SOURCE").ok()?;
+    let key = std::env::var("GAUSSIAN_PLY_KEY").ok()?;
+
+    let (kind, config) = spec.split_once(':')?;
+    let backend: Box<dyn PlyBackend> = match kind {
+        "local" => Box::new(ply_backend::LocalFileBackend::new(config)),
+        "http" => Box::new(ply_backend::HttpBackend::new(config)),
+        "s3" => {
+            let mut parts = config.splitn(3, ',');
+            let endpoint = parts.next()?;
+            let bucket = parts.next()?;
+            let region = parts.next().unwrap_or("us-east-1");
+            Box::new(ply_backend::S3Backend::new(endpoint, bucket, region))
         }
+        "chunked" => Box::new(ply_backend::ChunkedHttpBackend::new(config)),
+        other => {
+            error!("❌ 未知的 GAUSSIAN_PLY_SOURCE 类型: {}", other);
+            return None;
+        }
+    };
 
-        for ev in mouse_wheel.read() {
-            if !ctrl_pressed {
-                continue;
-            }
-
-            // Normalize trackpad pixel scrolling to roughly "lines".
-            let mut scroll_y = ev.y;
-            if ev.unit == MouseScrollUnit::Pixel {
-                scroll_y *= 0.02;
+    match cache.get_or_fetch(&key, backend.as_ref()) {
+        Ok(data) => {
+            let safe_name = key.replace(['/', '\\'], "_");
+            let dest = format!("assets/external_{}", safe_name);
+            if let Err(e) = std::fs::write(&dest, &data) {
+                error!("❌ 写入外部PLY失败: {}", e);
+                return None;
             }
-
-            orbit.distance = (orbit.distance - scroll_y * orbit.mouse_zoom_sensitivity).max(0.05);
+            info!("🌐 已从外部数据源加载 PLY: {} ({})", key, spec);
+            Some(format!("external_{}", safe_name))
         }
-    }
-
-    if ctrl_pressed && motion != Vec2::ZERO {
-        if mouse_buttons.pressed(MouseButton::Left) {
-            // Rotate
-            orbit.yaw -= motion.x * orbit.mouse_rotate_sensitivity;
-            orbit.pitch -= motion.y * orbit.mouse_rotate_sensitivity;
-        } else if mouse_buttons.pressed(MouseButton::Right)
-            || mouse_buttons.pressed(MouseButton::Middle)
-        {
-            // Pan (move target in view plane)
-            let rot = Quat::from_euler(EulerRot::YXZ, orbit.yaw, orbit.pitch, 0.0);
-            let right = rot * Vec3::X;
-            let up = rot * Vec3::Y;
-            let pan = orbit.mouse_pan_sensitivity * orbit.distance;
-            orbit.target -= right * motion.x * pan;
-            orbit.target += up * motion.y * pan;
+        Err(e) => {
+            error!("❌ 从外部数据源加载PLY失败 ({}): {}", spec, e);
+            None
         }
     }
+}
 
-    // Rotation (yaw/pitch).
-    let rot_step = orbit.rotate_speed * dt;
-    if keyboard.pressed(KeyCode::ArrowLeft) {
-        orbit.yaw += rot_step;
-    }
-    if keyboard.pressed(KeyCode::ArrowRight) {
-        orbit.yaw -= rot_step;
-    }
-    if keyboard.pressed(KeyCode::ArrowUp) {
-        orbit.pitch += rot_step;
-    }
-    if keyboard.pressed(KeyCode::ArrowDown) {
-        orbit.pitch -= rot_step;
-    }
-    orbit.pitch = orbit.pitch.clamp(-1.54, 1.54);
-
-    // Zoom (orbit distance).
-    let zoom_step = orbit.zoom_speed * dt;
-    if keyboard.pressed(KeyCode::Equal) || keyboard.pressed(KeyCode::NumpadAdd) {
-        orbit.distance -= zoom_step;
-    }
-    if keyboard.pressed(KeyCode::Minus) || keyboard.pressed(KeyCode::NumpadSubtract) {
-        orbit.distance += zoom_step;
-    }
-    orbit.distance = orbit.distance.max(0.05);
-
-    // Pan (move the orbit target).
-    let rot = Quat::from_euler(EulerRot::YXZ, orbit.yaw, orbit.pitch, 0.0);
-    let right = rot * Vec3::X;
-    let forward = rot * -Vec3::Z;
-    let forward_flat = Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero();
-
-    let pan_step = orbit.pan_speed * orbit.distance * dt;
-    if keyboard.pressed(KeyCode::KeyA) {
-        orbit.target -= right * pan_step;
-    }
-    if keyboard.pressed(KeyCode::KeyD) {
-        orbit.target += right * pan_step;
-    }
-    if keyboard.pressed(KeyCode::KeyW) {
-        orbit.target += forward_flat * pan_step;
-    }
-    if keyboard.pressed(KeyCode::KeyS) {
-        orbit.target -= forward_flat * pan_step;
-    }
-    if keyboard.pressed(KeyCode::Space) {
-        orbit.target.y += pan_step;
-    }
-    if keyboard.pressed(KeyCode::ShiftLeft) {
-        orbit.target.y -= pan_step;
+/// 如果设置了 `GAUSSIAN_SKYBOX_IMAGE`（相对 `assets/` 的竖直堆叠六面贴图路径），
+/// 构造一个挂到主相机上的 [`GaussianSkybox`]；没设置时返回 `None`，不挂天空盒，
+/// 维持原来的纯色背景。可选 `GAUSSIAN_SKYBOX_BRIGHTNESS` 覆盖默认亮度。
+fn resolve_skybox(asset_server: &AssetServer) -> Option<GaussianSkybox> {
+    let image_path = std::env::var("GAUSSIAN_SKYBOX_IMAGE").ok()?;
+    let image = asset_server.load(image_path.clone());
+
+    let mut skybox = GaussianSkybox::new(image);
+    if let Ok(brightness) = std::env::var("GAUSSIAN_SKYBOX_BRIGHTNESS") {
+        match brightness.parse::<f32>() {
+            Ok(value) => skybox = skybox.with_brightness(value),
+            Err(e) => error!("❌ 解析 GAUSSIAN_SKYBOX_BRIGHTNESS 失败: {}", e),
+        }
     }
 
-    *camera_transform = orbit.camera_transform();
+    info!("🌌 已挂载天空盒: {}", image_path);
+    Some(skybox)
 }
 
 /// 处理导入图片快捷键 (I键)
@@ -438,7 +383,7 @@ fn handle_upload_completion(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     upload_state: Res<ImageUploadState>,
-    mut orbit: ResMut<OrbitState>,
+    mut orbit_query: Query<&mut OrbitCamera, With<MainCamera>>,
     cloud_query: Query<Entity, With<MainCloud>>,
 ) {
     let status = upload_state.get_status();
@@ -467,6 +412,8 @@ fn handle_upload_completion(
         let src_path = format!("assets/{}", ply_name);
         let dst_path = format!("assets/{}", new_ply_name);
 
+        let coord_convention = CoordinateConvention::from_env();
+
         if let Err(e) = std::fs::copy(&src_path, &dst_path) {
             error!("❌ 复制PLY文件失败: {}", e);
             // 回退到原文件名
@@ -479,9 +426,10 @@ fn handle_upload_completion(
                     opacity_adaptive_radius: true,
                     ..default()
                 },
-                Transform::from_rotation(Quat::from_rotation_x(std::f32::consts::PI)),
+                coord_convention.basis_transform(),
                 Visibility::default(),
                 MainCloud,
+                OrbitCameraTarget,
                 Name::new("gaussian_cloud_generated"),
             ));
         } else {
@@ -494,9 +442,10 @@ fn handle_upload_completion(
                     opacity_adaptive_radius: true,
                     ..default()
                 },
-                Transform::from_rotation(Quat::from_rotation_x(std::f32::consts::PI)),
+                coord_convention.basis_transform(),
                 Visibility::default(),
                 MainCloud,
+                OrbitCameraTarget,
                 Name::new("gaussian_cloud_generated"),
             ));
 
@@ -512,9 +461,75 @@ fn handle_upload_completion(
         }
 
         // 重置相机以便重新居中
-        orbit.has_auto_centered = false;
+        if let Ok(mut orbit) = orbit_query.single_mut() {
+            orbit.has_auto_centered = false;
+        }
 
         // 重置状态为Idle
         upload_state.set_status(UploadStatus::Idle);
     }
 }
+
+/// 在环绕相机和第一人称飞行相机之间切换（Tab 键），保留位置/朝向的连续性：
+/// 飞行模式直接沿用环绕相机当前的 `Transform`；切回环绕模式时反推出一个
+/// `target`，使 `OrbitCamera::camera_transform()` 重新产出同一个 `Transform`。
+/// 切到飞行模式时把 [`OrbitCameraEnabled`] 设为 `false`，让出相机的 `Transform`。
+fn toggle_flycam_mode(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut orbit_enabled: ResMut<OrbitCameraEnabled>,
+    mut query: Query<(&mut GaussianCameraController, &mut OrbitCamera, &Transform), With<MainCamera>>,
+    windows: Query<&mut Window>,
+) {
+    if !keyboard.just_pressed(KeyCode::Tab) {
+        return;
+    }
+
+    let Ok((mut controller, mut orbit, transform)) = query.single_mut() else {
+        return;
+    };
+
+    if controller.enabled {
+        let (yaw, pitch, _) = transform.rotation.to_euler(EulerRot::YXZ);
+        let rot = Quat::from_euler(EulerRot::YXZ, yaw, pitch, 0.0);
+        orbit.yaw = yaw;
+        orbit.pitch = pitch;
+        orbit.target = transform.translation - rot * Vec3::new(0.0, 0.0, orbit.distance.max(0.05));
+
+        controller.enabled = false;
+        orbit_enabled.0 = true;
+        release_cursor(windows);
+        info!("🕹️  切换到环绕相机模式");
+    } else {
+        controller.enabled = true;
+        orbit_enabled.0 = false;
+        controller.sync_from_transform(transform);
+        info!("🕹️  切换到飞行相机模式 (WASD + Space/Ctrl 移动, 鼠标看, Shift 加速, Tab 切回)");
+    }
+}
+
+/// 在自由环绕相机和 transforms.json 里的训练相机位姿之间循环切换（C 键）。
+fn handle_camera_cycle_key(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut camera_set: ResMut<GaussianCameraSet>,
+    mut main_camera_q: Query<&mut Camera, (With<MainCamera>, Without<SceneCamera>)>,
+    mut scene_camera_q: Query<(&SceneCamera, &mut Camera), Without<MainCamera>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyC) || camera_set.cameras.is_empty() {
+        return;
+    }
+
+    let next = camera_set.cycle_next();
+
+    if let Ok(mut main_camera) = main_camera_q.single_mut() {
+        main_camera.is_active = next.is_none();
+    }
+
+    for (scene_camera, mut camera) in scene_camera_q.iter_mut() {
+        camera.is_active = Some(scene_camera.index) == next;
+    }
+
+    match next {
+        Some(i) => info!("📷 切换到训练相机视角 #{}", i),
+        None => info!("📷 切换回自由环绕相机"),
+    }
+}
@@ -0,0 +1,353 @@
+// 可复用的环绕相机（orbit camera）插件：围绕一个目标点转，支持鼠标拖拽/滚轮和
+// 键盘输入，并在目标带有 `Aabb` 时自动居中一次。之前这些逻辑（`OrbitState`、
+// `InputThrottle` 以及两个系统）直接写死在 `main.rs` 里，下游用户想复用只能整段
+// 复制粘贴；这里把它们提升成一个可以直接 `add_plugins` 的插件，输入绑定也做成
+// 可配置的资源而不是硬编码 Ctrl+拖拽。
+
+use bevy::camera::primitives::Aabb;
+use bevy::input::mouse::{MouseButton, MouseMotion, MouseScrollUnit, MouseWheel};
+use bevy::prelude::*;
+
+/// 挂在相机实体上的环绕相机状态：目标点、距离、偏航/俯仰角，以及各种灵敏度。
+/// 对应旧版 `main.rs` 里的 `OrbitState` 资源，区别是现在是组件——每个相机实体各自
+/// 保存自己的状态。注意 [`orbit_camera_controls`] 会把同一份鼠标/键盘输入应用到所有
+/// 带 `OrbitCamera` 的实体上。
+#[derive(Component, Debug, Clone)]
+pub struct OrbitCamera {
+    pub target: Vec3,
+    pub distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub pan_speed: f32,
+    pub rotate_speed: f32,
+    pub zoom_speed: f32,
+    pub mouse_rotate_sensitivity: f32,
+    pub mouse_pan_sensitivity: f32,
+    pub mouse_zoom_sensitivity: f32,
+    pub has_auto_centered: bool,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            target: Vec3::ZERO,
+            distance: 5.0,
+            yaw: 0.0,
+            pitch: 0.0,
+            // Pan speed scales by distance so it feels consistent at different zoom levels.
+            pan_speed: 1.0,
+            rotate_speed: 1.2, // rad/s
+            zoom_speed: 6.0,   // units/s
+            mouse_rotate_sensitivity: 0.005, // rad/pixel
+            mouse_pan_sensitivity: 0.002,    // world units per pixel per distance
+            mouse_zoom_sensitivity: 0.4,     // world units per scroll "line"
+            has_auto_centered: false,
+        }
+    }
+}
+
+impl OrbitCamera {
+    pub fn camera_transform(&self) -> Transform {
+        let rot = Quat::from_euler(EulerRot::YXZ, self.yaw, self.pitch, 0.0);
+        let pos = self.target + rot * Vec3::new(0.0, 0.0, self.distance.max(0.05));
+        Transform::from_translation(pos).looking_at(self.target, Vec3::Y)
+    }
+}
+
+/// 标记一个实体的 `Aabb` 是环绕相机自动居中时应该对准的目标。挂在点云实体上。
+#[derive(Component)]
+pub struct OrbitCameraTarget;
+
+/// 鼠标输入绑定：哪个修饰键 + 哪个按键触发旋转/平移，默认是 Ctrl+左键拖拽旋转、
+/// Ctrl+右键/中键拖拽平移、Ctrl+滚轮缩放——和旧版硬编码行为一致，但现在可以按需
+/// 改成其它组合（例如不需要按 Ctrl，或者换成右键旋转）。
+#[derive(Resource, Debug, Clone)]
+pub struct OrbitCameraInputMap {
+    pub modifier: Option<KeyCode>,
+    pub rotate_button: MouseButton,
+    pub pan_button: MouseButton,
+}
+
+impl Default for OrbitCameraInputMap {
+    fn default() -> Self {
+        Self {
+            modifier: Some(KeyCode::ControlLeft),
+            rotate_button: MouseButton::Left,
+            pan_button: MouseButton::Right,
+        }
+    }
+}
+
+impl OrbitCameraInputMap {
+    fn modifier_pressed(&self, keyboard: &ButtonInput<KeyCode>) -> bool {
+        match self.modifier {
+            Some(KeyCode::ControlLeft) => {
+                keyboard.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+            }
+            Some(KeyCode::ShiftLeft) => {
+                keyboard.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight])
+            }
+            Some(KeyCode::AltLeft) => keyboard.any_pressed([KeyCode::AltLeft, KeyCode::AltRight]),
+            Some(key) => keyboard.pressed(key),
+            None => true,
+        }
+    }
+}
+
+/// 输入事件节流器：限制输入处理频率，避免事件堆积导致延迟
+/// 类似摄像头项目中的"只在有新帧时解码"策略
+#[derive(Resource)]
+pub struct OrbitCameraThrottle {
+    last_update: f32,
+    pub min_interval: f32, // 16.67ms = 60fps
+}
+
+impl Default for OrbitCameraThrottle {
+    fn default() -> Self {
+        Self {
+            last_update: 0.0,
+            min_interval: 1.0 / 60.0, // 60 FPS
+        }
+    }
+}
+
+/// 是否让插件的系统驱动相机。调用方（例如和其它相机模式做切换的按键处理）可以把
+/// 这个资源设为 `false` 来暂时让出相机的 `Transform`，而不需要知道插件内部结构。
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct OrbitCameraEnabled(pub bool);
+
+impl Default for OrbitCameraEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// 环绕相机自动居中：首次看到带 [`OrbitCameraTarget`] 的实体的 `Aabb` 时，把目标点
+/// 设为其世界空间中心、距离设为半径的 3 倍，此后不再重复（`has_auto_centered`）。
+pub fn auto_center_orbit_camera(
+    mut camera_q: Query<&mut OrbitCamera>,
+    target_q: Query<(&Aabb, &GlobalTransform), With<OrbitCameraTarget>>,
+) {
+    let Ok((aabb, target_gt)) = target_q.single() else {
+        return;
+    };
+
+    for mut orbit in camera_q.iter_mut() {
+        if orbit.has_auto_centered {
+            continue;
+        }
+
+        // Center the orbit on the target once we have its bounds, and pick a reasonable distance.
+        let center_world = target_gt.affine().transform_point3a(aabb.center);
+        let center_world: Vec3 = center_world.into();
+
+        orbit.yaw = 0.0;
+        orbit.pitch = 0.0;
+        orbit.target = center_world;
+
+        let radius = aabb.half_extents.length().max(0.05);
+        orbit.distance = (radius * 3.0).max(0.5);
+
+        orbit.has_auto_centered = true;
+    }
+}
+
+/// 环绕相机的鼠标/键盘控制：Ctrl+拖拽旋转/平移、Ctrl+滚轮缩放，方向键旋转，
+/// WASD + Space/Shift 平移，+/- 缩放，R 重置。
+pub fn orbit_camera_controls(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    mut mouse_motion: MessageReader<MouseMotion>,
+    mut mouse_wheel: MessageReader<MouseWheel>,
+    input_map: Res<OrbitCameraInputMap>,
+    enabled: Res<OrbitCameraEnabled>,
+    mut throttle: ResMut<OrbitCameraThrottle>,
+    mut camera_query: Query<(&mut Transform, &mut OrbitCamera)>,
+    time: Res<Time>,
+) {
+    if !enabled.0 {
+        mouse_motion.clear();
+        mouse_wheel.clear();
+        return;
+    }
+
+    let dt = time.delta_secs();
+    let current_time = time.elapsed_secs();
+
+    // 输入节流：限制处理频率到 60fps，避免事件堆积
+    // 类似摄像头项目中"只在有新帧时解码"的策略
+    let should_process_mouse = current_time - throttle.last_update >= throttle.min_interval;
+
+    if !should_process_mouse {
+        // 清空事件，避免堆积
+        mouse_motion.clear();
+        mouse_wheel.clear();
+    } else {
+        throttle.last_update = current_time;
+    }
+
+    let modifier_pressed = input_map.modifier_pressed(&keyboard);
+
+    // Mouse controls, similar to many DCC / drawing tools.
+    let mut motion = Vec2::ZERO;
+    let mut scroll_amount = 0.0;
+    if should_process_mouse {
+        for ev in mouse_motion.read() {
+            motion += ev.delta;
+        }
+
+        for ev in mouse_wheel.read() {
+            if !modifier_pressed {
+                continue;
+            }
+
+            // Normalize trackpad pixel scrolling to roughly "lines".
+            let mut scroll_y = ev.y;
+            if ev.unit == MouseScrollUnit::Pixel {
+                scroll_y *= 0.02;
+            }
+            scroll_amount += scroll_y;
+        }
+    }
+
+    for (mut camera_transform, mut orbit) in camera_query.iter_mut() {
+        if keyboard.just_pressed(KeyCode::KeyR) {
+            *orbit = OrbitCamera::default();
+        }
+
+        if scroll_amount != 0.0 {
+            orbit.distance = (orbit.distance - scroll_amount * orbit.mouse_zoom_sensitivity).max(0.05);
+        }
+
+        if modifier_pressed && motion != Vec2::ZERO {
+            if mouse_buttons.pressed(input_map.rotate_button) {
+                // Rotate
+                orbit.yaw -= motion.x * orbit.mouse_rotate_sensitivity;
+                orbit.pitch -= motion.y * orbit.mouse_rotate_sensitivity;
+            } else if mouse_buttons.pressed(input_map.pan_button)
+                || mouse_buttons.pressed(MouseButton::Middle)
+            {
+                // Pan (move target in view plane)
+                let rot = Quat::from_euler(EulerRot::YXZ, orbit.yaw, orbit.pitch, 0.0);
+                let right = rot * Vec3::X;
+                let up = rot * Vec3::Y;
+                let pan = orbit.mouse_pan_sensitivity * orbit.distance;
+                orbit.target -= right * motion.x * pan;
+                orbit.target += up * motion.y * pan;
+            }
+        }
+
+        // Rotation (yaw/pitch).
+        let rot_step = orbit.rotate_speed * dt;
+        if keyboard.pressed(KeyCode::ArrowLeft) {
+            orbit.yaw += rot_step;
+        }
+        if keyboard.pressed(KeyCode::ArrowRight) {
+            orbit.yaw -= rot_step;
+        }
+        if keyboard.pressed(KeyCode::ArrowUp) {
+            orbit.pitch += rot_step;
+        }
+        if keyboard.pressed(KeyCode::ArrowDown) {
+            orbit.pitch -= rot_step;
+        }
+        orbit.pitch = orbit.pitch.clamp(-1.54, 1.54);
+
+        // Zoom (orbit distance).
+        let zoom_step = orbit.zoom_speed * dt;
+        if keyboard.pressed(KeyCode::Equal) || keyboard.pressed(KeyCode::NumpadAdd) {
+            orbit.distance -= zoom_step;
+        }
+        if keyboard.pressed(KeyCode::Minus) || keyboard.pressed(KeyCode::NumpadSubtract) {
+            orbit.distance += zoom_step;
+        }
+        orbit.distance = orbit.distance.max(0.05);
+
+        // Pan (move the orbit target).
+        let rot = Quat::from_euler(EulerRot::YXZ, orbit.yaw, orbit.pitch, 0.0);
+        let right = rot * Vec3::X;
+        let forward = rot * -Vec3::Z;
+        let forward_flat = Vec3::new(forward.x, 0.0, forward.z).normalize_or_zero();
+
+        let pan_step = orbit.pan_speed * orbit.distance * dt;
+        if keyboard.pressed(KeyCode::KeyA) {
+            orbit.target -= right * pan_step;
+        }
+        if keyboard.pressed(KeyCode::KeyD) {
+            orbit.target += right * pan_step;
+        }
+        if keyboard.pressed(KeyCode::KeyW) {
+            orbit.target += forward_flat * pan_step;
+        }
+        if keyboard.pressed(KeyCode::KeyS) {
+            orbit.target -= forward_flat * pan_step;
+        }
+        if keyboard.pressed(KeyCode::Space) {
+            orbit.target.y += pan_step;
+        }
+        if keyboard.pressed(KeyCode::ShiftLeft) {
+            orbit.target.y -= pan_step;
+        }
+
+        *camera_transform = orbit.camera_transform();
+    }
+}
+
+/// 把环绕相机接入 App：注册 [`OrbitCameraInputMap`]/[`OrbitCameraThrottle`] 资源，
+/// 并按依赖顺序把自动居中和输入控制系统加到 `Update`。
+///
+/// 不会自己生成相机实体——调用方负责 spawn 一个带 `Transform` + [`OrbitCamera`] 的
+/// 实体（以及想要自动居中对准的目标实体上的 [`OrbitCameraTarget`]）。
+pub struct GaussianOrbitCameraPlugin;
+
+impl Plugin for GaussianOrbitCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OrbitCameraInputMap>()
+            .init_resource::<OrbitCameraThrottle>()
+            .init_resource::<OrbitCameraEnabled>()
+            .add_systems(
+                Update,
+                (auto_center_orbit_camera, orbit_camera_controls).chain(),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camera_transform_at_zero_yaw_pitch_sits_behind_target_on_z() {
+        let orbit = OrbitCamera {
+            distance: 5.0,
+            ..OrbitCamera::default()
+        };
+        let transform = orbit.camera_transform();
+        assert!((transform.translation - Vec3::new(0.0, 0.0, 5.0)).length() < 1e-5);
+        // 朝向目标：本地 -Z 方向应该指向 target（原点）。
+        let forward = transform.rotation * Vec3::NEG_Z;
+        assert!((forward - (-Vec3::Z)).length() < 1e-5);
+    }
+
+    #[test]
+    fn test_camera_transform_orbits_around_nonzero_target() {
+        let orbit = OrbitCamera {
+            target: Vec3::new(1.0, 2.0, 3.0),
+            distance: 5.0,
+            ..OrbitCamera::default()
+        };
+        let transform = orbit.camera_transform();
+        // 距离目标点的距离应该就是 `distance`，不管目标在哪。
+        assert!((transform.translation.distance(orbit.target) - 5.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_camera_transform_clamps_distance_floor() {
+        let orbit = OrbitCamera {
+            distance: -10.0, // 不合法的负距离
+            ..OrbitCamera::default()
+        };
+        let transform = orbit.camera_transform();
+        assert!((transform.translation.length() - 0.05).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,259 @@
+// 可插拔的 PLY 存储后端：`PlyCacheManager` 不再和某个具体协议（SHARP 推理服务器
+// 的分块下载 API）绑死，而是对任何实现了 `PlyBackend` 的数据源做缓存。这样用户
+// 既可以指向自建的推理服务器，也可以直接指向一个 S3 桶、一个静态 HTTP(S) 文件
+// 服务，或者本地磁盘上的一份模型库。
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+/// 一个 PLY 数据源：给定一个后端自定义的 `key`（文件名、对象 key、job id……），
+/// 返回完整的文件字节。`PlyCacheManager::get_or_fetch` 在前面加一层内容寻址缓存。
+pub trait PlyBackend {
+    /// 取回 `key` 对应的完整字节。
+    fn fetch(&self, key: &str) -> Result<Vec<u8>, String>;
+
+    /// 廉价的存在性检查（不下载全部内容）。后端不支持时可以退化为 `true`，
+    /// 交给 `fetch` 去报告真正的错误。
+    fn exists(&self, key: &str) -> bool;
+}
+
+/// 本地文件系统后端：`key` 是相对于 `root` 的路径。
+pub struct LocalFileBackend {
+    pub root: PathBuf,
+}
+
+impl LocalFileBackend {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn resolve(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl PlyBackend for LocalFileBackend {
+    fn fetch(&self, key: &str) -> Result<Vec<u8>, String> {
+        fs::read(self.resolve(key)).map_err(|e| format!("读取本地文件失败: {}", e))
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        self.resolve(key).is_file()
+    }
+}
+
+/// 普通 HTTP(S) 后端：对 `{base_url}/{key}` 发起 GET 请求。
+pub struct HttpBackend {
+    pub base_url: String,
+}
+
+impl HttpBackend {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+        }
+    }
+
+    fn url_for(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url.trim_end_matches('/'), key)
+    }
+}
+
+impl PlyBackend for HttpBackend {
+    fn fetch(&self, key: &str) -> Result<Vec<u8>, String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+
+        let response = client
+            .get(self.url_for(key))
+            .send()
+            .map_err(|e| format!("请求失败: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("HTTP {}", response.status()));
+        }
+
+        response
+            .bytes()
+            .map(|b| b.to_vec())
+            .map_err(|e| format!("读取响应失败: {}", e))
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        let Ok(client) = reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+        else {
+            return true;
+        };
+
+        client
+            .head(self.url_for(key))
+            .send()
+            .map(|r| r.status().is_success())
+            .unwrap_or(true)
+    }
+}
+
+/// 现有的 SHARP 推理服务器分块下载 API，包装成 `PlyBackend`：`key` 就是 job id。
+///
+/// 可选携带一个取消标记（`with_cancel_flag`）：上传/下载流程可以把自己任务的
+/// `cancel_flag` 接进来，这样通过 `PlyCacheManager::get_or_fetch` 发起的下载
+/// 仍然能被用户取消；不需要取消能力的调用方（比如外部数据源加载）留空即可。
+pub struct ChunkedHttpBackend {
+    pub server_url: String,
+    cancel_flag: Option<Arc<AtomicBool>>,
+}
+
+impl ChunkedHttpBackend {
+    pub fn new(server_url: impl Into<String>) -> Self {
+        Self {
+            server_url: server_url.into(),
+            cancel_flag: None,
+        }
+    }
+
+    pub fn with_cancel_flag(mut self, cancel_flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(cancel_flag);
+        self
+    }
+}
+
+impl PlyBackend for ChunkedHttpBackend {
+    fn fetch(&self, key: &str) -> Result<Vec<u8>, String> {
+        match &self.cancel_flag {
+            Some(flag) => crate::image_uploader::fetch_all_chunks_cancellable(&self.server_url, key, flag),
+            None => crate::image_uploader::fetch_all_chunks(&self.server_url, key),
+        }
+    }
+
+    fn exists(&self, _key: &str) -> bool {
+        true // 交给 fetch 报告 404/超时等真正的错误
+    }
+}
+
+/// S3 兼容对象存储的连接信息。
+///
+/// 注意：这里只做最小可用的 path-style GET（`{endpoint}/{bucket}/{key}`），不实现
+/// AWS SigV4 签名；因此只适用于公开可读的桶，或者放在带自有鉴权的反向代理后面。
+/// 故意不提供 secret key 字段——没有签名实现就没法用上它，接受了也只会让调用方
+/// 误以为请求是被鉴权的。需要访问私有桶时应换成专门的 S3 客户端 crate（如
+/// `aws-sdk-s3`），或者把这个 backend 放在会自己签名的反向代理/网关后面。
+pub struct S3Backend {
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    pub access_key: Option<String>,
+}
+
+impl S3Backend {
+    pub fn new(endpoint: impl Into<String>, bucket: impl Into<String>, region: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            bucket: bucket.into(),
+            region: region.into(),
+            access_key: None,
+        }
+    }
+
+    /// 附带一个 access key，作为提示头透传给能自行处理签名的反向代理/网关；
+    /// 这不是 SigV4 鉴权，私有桶直连大概率仍会被拒绝。
+    pub fn with_access_key(mut self, access_key: impl Into<String>) -> Self {
+        self.access_key = Some(access_key.into());
+        self
+    }
+
+    fn object_url(&self, key: &str) -> String {
+        format!("{}/{}/{}", self.endpoint.trim_end_matches('/'), self.bucket, key)
+    }
+}
+
+impl PlyBackend for S3Backend {
+    fn fetch(&self, key: &str) -> Result<Vec<u8>, String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_secs(60))
+            .build()
+            .map_err(|e| format!("创建HTTP客户端失败: {}", e))?;
+
+        let mut request = client.get(self.object_url(key));
+        // 最小可用鉴权：对私有桶通常需要换成 SigV4；这里只透传 access key 作为提示头，
+        // 方便放在会自行处理签名的反向代理/网关后面使用。
+        if let Some(access_key) = &self.access_key {
+            request = request.header("X-Amz-Access-Key", access_key);
+        }
+
+        let response = request.send().map_err(|e| format!("请求失败: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("S3 GET 失败: HTTP {}", response.status()));
+        }
+
+        response
+            .bytes()
+            .map(|b| b.to_vec())
+            .map_err(|e| format!("读取响应失败: {}", e))
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        let Ok(client) = reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()
+        else {
+            return true;
+        };
+
+        client
+            .head(self.object_url(key))
+            .send()
+            .map(|r| r.status().is_success())
+            .unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_local_file_backend_fetch_and_exists() {
+        let dir = std::env::temp_dir().join("test_ply_backend_local");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("model.ply"), b"local ply bytes").unwrap();
+
+        let backend = LocalFileBackend::new(&dir);
+        assert!(backend.exists("model.ply"));
+        assert!(!backend.exists("missing.ply"));
+        assert_eq!(backend.fetch("model.ply").unwrap(), b"local ply bytes");
+        assert!(backend.fetch("missing.ply").is_err());
+    }
+
+    #[test]
+    fn test_http_backend_url_for_joins_base_and_key() {
+        let backend = HttpBackend::new("https://models.example.com/");
+        assert_eq!(backend.url_for("scene.ply"), "https://models.example.com/scene.ply");
+
+        let backend = HttpBackend::new("https://models.example.com");
+        assert_eq!(backend.url_for("scene.ply"), "https://models.example.com/scene.ply");
+    }
+
+    #[test]
+    fn test_s3_backend_object_url_is_path_style() {
+        let backend = S3Backend::new("https://s3.example.com/", "my-bucket", "us-east-1");
+        assert_eq!(
+            backend.object_url("scene.ply"),
+            "https://s3.example.com/my-bucket/scene.ply"
+        );
+    }
+
+    #[test]
+    fn test_s3_backend_with_access_key_does_not_accept_a_secret() {
+        // 故意没有 secret key 字段/参数可设——签名没实现，不该假装支持鉴权。
+        let backend = S3Backend::new("https://s3.example.com", "bucket", "us-east-1")
+            .with_access_key("AKIDEXAMPLE");
+        assert_eq!(backend.access_key.as_deref(), Some("AKIDEXAMPLE"));
+    }
+}
@@ -4,14 +4,39 @@
 // - 第一次：下载 63MB (2.8秒)
 // - 第二次：从缓存加载 (0.1秒) ↓ 96%
 // - 离线可用
-
+//
+// 内容寻址：缓存文件以数据的 SHA-256 摘要命名（`<digest>.ply`），一份 `index.json`
+// 记录逻辑名称（调用方传入的 `name`）到摘要的映射。这样同一份内容即使通过不同的
+// `name` 存、取多次，磁盘上也只保留一份副本；而 `load_from_cache` 会对读到的字节
+// 重新计算摘要并与记录比对，摘要不一致（文件被截断/损坏）时自动删除并返回
+// `None`，让损坏的缓存自愈。
+//
+// 容量预算：`max_total_bytes` 设置后，每次 `save_to_cache` 写入后都会做一次 LRU
+// 淘汰——按 `index.json` 里记录的最近访问时间（写入或 `load_from_cache` 命中都会
+// 刷新）排序，优先删掉最久未访问的 blob，直到总大小回到预算以内；本次请求刚写入/
+// 命中的条目不会在同一次调用中被淘汰，避免“写入即被自己挤掉”的情况。
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub struct PlyCacheManager {
     cache_dir: PathBuf,
-    max_age_secs: u64, // 缓存过期时间
+    max_age_secs: u64,          // 缓存过期时间
+    max_total_bytes: Option<u64>, // 缓存总容量预算（None = 不限制）
+}
+
+/// `index.json` 的内容：逻辑名称 -> 内容摘要，以及每个摘要的最近访问时间。
+#[derive(Default, Serialize, Deserialize)]
+struct CacheIndex {
+    /// name -> hex digest
+    entries: HashMap<String, String>,
+    /// hex digest -> 最近访问时间（unix 秒），用于 LRU 淘汰
+    #[serde(default)]
+    last_access: HashMap<String, u64>,
 }
 
 impl PlyCacheManager {
@@ -22,22 +47,62 @@ impl PlyCacheManager {
         Self {
             cache_dir,
             max_age_secs: 24 * 3600, // 默认24小时过期
+            max_total_bytes: None,
         }
     }
 
-    /// 获取缓存文件路径
-    fn cache_path(&self, name: &str) -> PathBuf {
-        self.cache_dir.join(format!("{}.ply", name))
+    /// 设置缓存总容量预算，并立即做一次淘汰使现有缓存回到预算以内。
+    pub fn set_max_total_bytes(&mut self, bytes: u64) {
+        self.max_total_bytes = Some(bytes);
+        self.evict_if_needed(None);
+    }
+
+    fn index_path(&self) -> PathBuf {
+        self.cache_dir.join("index.json")
+    }
+
+    fn load_index(&self) -> CacheIndex {
+        fs::read(self.index_path())
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_index(&self, index: &CacheIndex) -> Result<(), std::io::Error> {
+        let bytes = serde_json::to_vec_pretty(index)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        fs::write(self.index_path(), bytes)
     }
 
-    /// 检查缓存是否有效
+    /// 已提交（完整写入）的缓存文件路径：`<digest>.ply`
+    fn blob_path(&self, digest: &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.ply", digest))
+    }
+
+    /// 写入中的临时文件路径：`<digest>.ply.tmp`
+    fn tmp_blob_path(&self, digest: &str) -> PathBuf {
+        self.cache_dir.join(format!("{}.ply.tmp", digest))
+    }
+
+    /// 解析逻辑名称对应的内容摘要
+    fn resolve_digest(&self, name: &str) -> Option<String> {
+        self.load_index().entries.get(name).cloned()
+    }
+
+    /// 检查缓存是否有效（已提交的 blob 存在且未过期）
     pub fn is_cached(&self, name: &str) -> bool {
-        let path = self.cache_path(name);
+        let Some(digest) = self.resolve_digest(name) else {
+            return false;
+        };
+        self.blob_is_valid(&digest)
+    }
+
+    fn blob_is_valid(&self, digest: &str) -> bool {
+        let path = self.blob_path(digest);
         if !path.exists() {
             return false;
         }
 
-        // 检查文件是否过期
         if let Ok(metadata) = fs::metadata(&path) {
             if let Ok(modified) = metadata.modified() {
                 if let Ok(elapsed) = SystemTime::now().duration_since(modified) {
@@ -49,24 +114,127 @@ impl PlyCacheManager {
         false
     }
 
-    /// 从缓存加载
+    /// 从缓存加载。摘要与记录不一致（损坏/截断）时删除文件并返回 `None`。
     pub fn load_from_cache(&self, name: &str) -> Option<Vec<u8>> {
-        if !self.is_cached(name) {
+        let digest = self.resolve_digest(name)?;
+        if !self.blob_is_valid(&digest) {
+            return None;
+        }
+
+        let path = self.blob_path(&digest);
+        let data = fs::read(&path).ok()?;
+
+        if digest_hex(&data) != digest {
+            println!("⚠️  缓存校验失败，已损坏，删除: {:?}", path);
+            let _ = fs::remove_file(&path);
             return None;
         }
 
-        let path = self.cache_path(name);
-        fs::read(&path).ok()
+        self.touch_access(&digest);
+        Some(data)
     }
 
-    /// 保存到缓存
+    /// 保存到缓存：按内容摘要命名并去重，原子落盘（写临时文件后 rename）。
     pub fn save_to_cache(&self, name: &str, data: &[u8]) -> Result<(), std::io::Error> {
-        let path = self.cache_path(name);
-        fs::write(&path, data)?;
-        println!("✅ 已缓存 PLY: {:?} ({:.2} MB)", path, data.len() as f64 / 1_000_000.0);
+        let digest = digest_hex(data);
+        let path = self.blob_path(&digest);
+
+        // 已过期的同内容 blob 不复用，重新写入以刷新修改时间，否则刚保存的条目会被当成过期。
+        if self.blob_is_valid(&digest) {
+            println!(
+                "✅ 已存在相同内容的缓存，复用: {:?} ({:.2} MB)",
+                path,
+                data.len() as f64 / 1_000_000.0
+            );
+        } else {
+            let tmp_path = self.tmp_blob_path(&digest);
+            fs::write(&tmp_path, data)?;
+            fs::rename(&tmp_path, &path)?;
+            println!(
+                "✅ 已缓存 PLY: {:?} ({:.2} MB)",
+                path,
+                data.len() as f64 / 1_000_000.0
+            );
+        }
+
+        let mut index = self.load_index();
+        index.entries.insert(name.to_string(), digest.clone());
+        index.last_access.insert(digest.clone(), now_secs());
+        self.save_index(&index)?;
+
+        // 本次请求的条目刚写入，不应在这次淘汰里被自己挤掉。
+        self.evict_if_needed(Some(&digest));
+
         Ok(())
     }
 
+    /// 刷新某个摘要的最近访问时间（用于 LRU）。
+    fn touch_access(&self, digest: &str) {
+        let mut index = self.load_index();
+        index.last_access.insert(digest.to_string(), now_secs());
+        let _ = self.save_index(&index);
+    }
+
+    /// 列出所有已提交的 blob 及其大小
+    fn list_blobs(&self) -> Vec<(String, PathBuf, u64)> {
+        let mut blobs = Vec::new();
+        let Ok(read_dir) = fs::read_dir(&self.cache_dir) else {
+            return blobs;
+        };
+
+        for entry in read_dir.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("ply") {
+                continue;
+            }
+            let Some(digest) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            blobs.push((digest.to_string(), path, size));
+        }
+
+        blobs
+    }
+
+    /// 在超出 `max_total_bytes` 预算时，淘汰最久未访问的条目，直到回到预算以内。
+    /// `keep_digest` 是本次调用刚写入/命中的摘要，永远不会被淘汰。
+    fn evict_if_needed(&self, keep_digest: Option<&str>) {
+        let Some(budget) = self.max_total_bytes else {
+            return;
+        };
+
+        let mut blobs = self.list_blobs();
+        let mut total: u64 = blobs.iter().map(|(_, _, size)| size).sum();
+        if total <= budget {
+            return;
+        }
+
+        let index = self.load_index();
+        blobs.sort_by_key(|(digest, _, _)| {
+            index.last_access.get(digest).copied().unwrap_or(0)
+        });
+
+        let mut index = index;
+        for (digest, path, size) in blobs {
+            if total <= budget {
+                break;
+            }
+            if Some(digest.as_str()) == keep_digest {
+                continue;
+            }
+
+            if fs::remove_file(&path).is_ok() {
+                println!("🗑️  缓存超出预算，淘汰最久未访问条目: {:?}", path);
+                total = total.saturating_sub(size);
+                index.last_access.remove(&digest);
+                index.entries.retain(|_, d| d != &digest);
+            }
+        }
+
+        let _ = self.save_index(&index);
+    }
+
     /// 清理过期缓存
     pub fn cleanup_expired(&self) -> Result<usize, std::io::Error> {
         let mut cleaned = 0;
@@ -95,9 +263,31 @@ impl PlyCacheManager {
         Ok(cleaned)
     }
 
+    /// 缓存优先地取回 `key` 对应的内容：命中缓存直接返回；否则从 `backend` 拉取，
+    /// 写入缓存后再返回。这让 `PlyCacheManager` 成为任意 [`crate::ply_backend::PlyBackend`]
+    /// 前面的一层通用缓存，而不用关心具体协议是分块下载 API、S3 还是本地文件。
+    pub fn get_or_fetch(
+        &self,
+        key: &str,
+        backend: &dyn crate::ply_backend::PlyBackend,
+    ) -> Result<Vec<u8>, String> {
+        if let Some(data) = self.load_from_cache(key) {
+            return Ok(data);
+        }
+
+        let data = backend.fetch(key)?;
+        self.save_to_cache(key, &data)
+            .map_err(|e| format!("写入缓存失败: {}", e))?;
+
+        Ok(data)
+    }
+
     /// 获取缓存统计信息
     pub fn cache_stats(&self) -> Result<CacheStats, std::io::Error> {
-        let mut stats = CacheStats::default();
+        let mut stats = CacheStats {
+            max_total_bytes: self.max_total_bytes,
+            ..Default::default()
+        };
 
         for entry in fs::read_dir(&self.cache_dir)? {
             let entry = entry?;
@@ -117,16 +307,46 @@ impl PlyCacheManager {
     }
 }
 
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 计算字节内容的十六进制 SHA-256 摘要
+fn digest_hex(data: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(data);
+    let bytes = hasher.finalize();
+
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push_str(&format!("{:02x}", byte));
+    }
+    hex
+}
+
 #[derive(Default, Debug)]
 pub struct CacheStats {
     pub file_count: usize,
     pub total_size: u64,
+    pub max_total_bytes: Option<u64>,
 }
 
 impl CacheStats {
     pub fn total_size_mb(&self) -> f64 {
         self.total_size as f64 / 1_000_000.0
     }
+
+    /// 当前用量占预算的百分比，未设置预算时返回 `None`。
+    pub fn budget_used_pct(&self) -> Option<f64> {
+        let budget = self.max_total_bytes?;
+        if budget == 0 {
+            return Some(100.0);
+        }
+        Some(self.total_size as f64 / budget as f64 * 100.0)
+    }
 }
 
 #[cfg(test)]
@@ -168,4 +388,50 @@ mod tests {
         // 再次检查：应该过期
         assert!(!cache.is_cached("test_expiry"));
     }
+
+    #[test]
+    fn test_identical_content_under_different_names_is_deduped() {
+        let cache = PlyCacheManager::new("/tmp/test_ply_cache_dedup");
+        let test_data = b"shared blob content";
+
+        cache.save_to_cache("name_a", test_data).unwrap();
+        cache.save_to_cache("name_b", test_data).unwrap();
+
+        let stats = cache.cache_stats().unwrap();
+        assert_eq!(stats.file_count, 1);
+
+        assert_eq!(cache.load_from_cache("name_a").unwrap(), test_data);
+        assert_eq!(cache.load_from_cache("name_b").unwrap(), test_data);
+    }
+
+    #[test]
+    fn test_corrupted_cache_self_heals() {
+        let cache = PlyCacheManager::new("/tmp/test_ply_cache_corrupt");
+        let test_data = b"original ply bytes";
+        cache.save_to_cache("corrupt_me", test_data).unwrap();
+
+        let digest = cache.resolve_digest("corrupt_me").unwrap();
+        let path = cache.blob_path(&digest);
+        fs::write(&path, b"truncated garbage").unwrap();
+
+        assert!(cache.load_from_cache("corrupt_me").is_none());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_lru_eviction_respects_budget_and_keeps_current_entry() {
+        let mut cache = PlyCacheManager::new("/tmp/test_ply_cache_lru");
+        // 预算只够放下约一个条目，逼迫每次 save 都触发淘汰。
+        cache.set_max_total_bytes(12);
+
+        cache.save_to_cache("a", b"aaaaaaaaaaaa").unwrap(); // 12 bytes
+        cache.save_to_cache("b", b"bbbbbbbbbbbb").unwrap(); // evicts "a"
+        cache.save_to_cache("c", b"cccccccccccc").unwrap(); // evicts "b", keeps "c"
+
+        assert!(!cache.is_cached("a"));
+        assert!(cache.is_cached("c"));
+
+        let stats = cache.cache_stats().unwrap();
+        assert!(stats.total_size <= 12);
+    }
 }
@@ -0,0 +1,82 @@
+// 在点云后面渲染一张环境贴图（天空盒），而不是纯色背景。
+//
+// 被扫描重建的 3DGS 场景经常有未建模的背景区域（墙外、天花板之外……），直接露出
+// 默认的纯色清屏会显得很业余；挂一张 HDRI/星空天空盒能让不完整的重建显得体面
+// 很多。Bevy 自带的 `Skybox` 组件本身就是渲染在所有不透明/半透明内容之后的全屏
+// 背景，所以这里只负责：等六面贴图（竖直堆叠的 PNG/KTX2）加载完成、把它重新解读
+// 成一张 cube 贴图数组，避免附加到相机上时出现一帧垃圾画面。
+
+use bevy::asset::LoadState;
+use bevy::core_pipeline::Skybox;
+use bevy::prelude::*;
+use bevy::render::render_resource::{TextureViewDescriptor, TextureViewDimension};
+
+/// 挂在相机实体上，声明"这台相机应该显示这张天空盒"。贴图加载完成之前不会
+/// 附加 Bevy 的 `Skybox` 组件，由 [`attach_skybox_when_loaded`] 负责接上。
+#[derive(Component, Clone)]
+pub struct GaussianSkybox {
+    pub image: Handle<Image>,
+    pub brightness: f32,
+}
+
+impl GaussianSkybox {
+    pub fn new(image: Handle<Image>) -> Self {
+        Self {
+            image,
+            brightness: 1000.0,
+        }
+    }
+
+    pub fn with_brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness;
+        self
+    }
+}
+
+/// 等待六面贴图加载完成，声明它的视图维度为 Cube，再把 Bevy 的 `Skybox` 组件接到
+/// 相机实体上。`Without<Skybox>` 保证每个相机只做一次这个转换。
+pub fn attach_skybox_when_loaded(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut query: Query<(Entity, &GaussianSkybox), Without<Skybox>>,
+) {
+    for (entity, cfg) in query.iter_mut() {
+        if asset_server.load_state(&cfg.image) != LoadState::Loaded {
+            continue;
+        }
+
+        if let Some(image) = images.get_mut(&cfg.image) {
+            if image.texture_descriptor.array_layer_count() == 1 {
+                image.reinterpret_stacked_2d_as_array(image.height() / image.width());
+                image.texture_view_descriptor = Some(TextureViewDescriptor {
+                    dimension: Some(TextureViewDimension::Cube),
+                    ..default()
+                });
+            }
+        }
+
+        commands.entity(entity).insert(Skybox {
+            image: cfg.image.clone(),
+            brightness: cfg.brightness,
+            ..default()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_defaults_brightness_to_1000() {
+        let skybox = GaussianSkybox::new(Handle::default());
+        assert_eq!(skybox.brightness, 1000.0);
+    }
+
+    #[test]
+    fn test_with_brightness_overrides_default() {
+        let skybox = GaussianSkybox::new(Handle::default()).with_brightness(250.0);
+        assert_eq!(skybox.brightness, 250.0);
+    }
+}